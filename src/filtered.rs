@@ -6,6 +6,9 @@
 //! let filtered = Filtered::from_str("foo::bar=trace,baz=debug");
 //! # assert!(filtered.filtered().any(|(k, &v)| { k == "foo::bar" && v == log::LevelFilter::Trace }));
 //! # assert!(filtered.filtered().any(|(k, &v)| { k == "baz" && v == log::LevelFilter::Debug }));
+//! let filtered: Filtered = "foo::bar=trace,baz=debug".parse().unwrap();
+//! # assert!(filtered.filtered().any(|(k, &v)| { k == "foo::bar" && v == log::LevelFilter::Trace }));
+//! # assert!(filtered.filtered().any(|(k, &v)| { k == "baz" && v == log::LevelFilter::Debug }));
 //! let filtered = Filtered::new(&["foo::bar=trace", "baz=debug"]);
 //! # assert!(filtered.filtered().any(|(k, &v)| { k == "foo::bar" && v == log::LevelFilter::Trace }));
 //! # assert!(filtered.filtered().any(|(k, &v)| { k == "baz" && v == log::LevelFilter::Debug }));
@@ -22,40 +25,267 @@
 //! ```
 use std::collections::HashMap;
 
-/// A simple target-filtering type
-#[derive(Default)]
+#[cfg(feature = "notify")]
+mod watch;
+#[cfg(feature = "notify")]
+pub use watch::{watch_file, FilterWatcher};
+
+/** A simple target-filtering type
+
+A target can use `*` as a whole `::` segment to match one or more segments, so `tokio::*`
+matches `tokio::net` and `hyper::*::client` matches `hyper::proto::h1::client`. A `*` that
+isn't a whole segment, like `foo*`, is matched literally. Targets without a `*` segment match
+that module and its children, so `mio` matches `mio` and `mio::uds` but not `mionext`.
+
+A target starting with `!` is an exclusion, which hides everything from that target and its
+children regardless of the level, so `tokio=trace,!tokio::io` hides `tokio::io::read` and
+everything else under `tokio` is filtered by the `tokio=trace` rule. Exclusions always win
+over the other rules, and anything after the `=` of an exclusion is ignored.
+
+A default level can be set with [`with_default`](#method.with_default), or with a bare level
+like `info` in the rules. Targets that don't match any rule show that level and up, like a bare
+`RUST_LOG=info` in `env_logger`.
+
+With the `regex` feature, rules can also be regular expressions, see
+[`new_regex`](#method.new_regex).
+*/
+#[derive(Clone, Default)]
 pub struct Filtered {
-    targets: HashMap<String, log::LevelFilter>,
+    targets: HashMap<String, Rule>,
+    exclusions: Vec<String>,
+    default: Option<log::LevelFilter>,
+    #[cfg(feature = "regex")]
+    regexes: Vec<(regex::Regex, log::LevelFilter)>,
+    files: Vec<(std::path::PathBuf, log::LevelFilter)>,
+    module_path: bool,
+    ignore_case: bool,
+}
+
+#[derive(Clone)]
+struct Rule {
+    level: log::LevelFilter,
+    // `None` for plain targets, which match that module and its children
+    glob: Option<Vec<Segment>>,
+}
+
+impl Rule {
+    fn new(target: &str, level: log::LevelFilter) -> Self {
+        let glob = if target.split("::").any(|segment| segment == "*") {
+            Some(
+                target
+                    .split("::")
+                    .map(|segment| match segment {
+                        "*" => Segment::Any,
+                        literal => Segment::Literal(literal.to_string()),
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        Self { level, glob }
+    }
+
+    fn matches(&self, target: &str, input: &str, ignore_case: bool) -> bool {
+        match &self.glob {
+            Some(glob) => matches_glob(glob, &input.split("::").collect::<Vec<_>>(), ignore_case),
+            None => in_module(input, target, ignore_case),
+        }
+    }
+}
+
+// whether the target is this module or one of its children
+fn in_module(target: &str, module: &str, ignore_case: bool) -> bool {
+    let (prefix, rest) = match (target.get(..module.len()), target.get(module.len()..)) {
+        (Some(prefix), Some(rest)) => (prefix, rest),
+        _ => return false,
+    };
+    same(prefix, module, ignore_case) && (rest.is_empty() || rest.starts_with("::"))
+}
+
+fn same(left: &str, right: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        left.eq_ignore_ascii_case(right)
+    } else {
+        left == right
+    }
+}
+
+#[derive(Clone)]
+enum Segment {
+    Any,
+    Literal(String),
+}
+
+// whether the glob matches the start of the segments, `Any` taking one or more of them
+fn matches_glob(glob: &[Segment], input: &[&str], ignore_case: bool) -> bool {
+    match glob.split_first() {
+        None => true,
+        Some((Segment::Literal(literal), rest)) => match input.split_first() {
+            Some((segment, input)) => {
+                same(segment, literal, ignore_case) && matches_glob(rest, input, ignore_case)
+            }
+            None => false,
+        },
+        Some((Segment::Any, rest)) => {
+            (1..=input.len()).any(|n| matches_glob(rest, &input[n..], ignore_case))
+        }
+    }
 }
 
 impl Filtered {
     /// Create a new filtered set from an iterator of strings
     ///
-    /// The format should be `target=level`
+    /// The format should be `target=level`. An empty or unknown level is `Off`, and only the
+    /// first `=` splits, so `a=b=c` has the level `b=c`. A target with no `=` uses the
+    /// [default](#method.with_default) level, and isn't filtered without one.
+    /// A level without a target, like `info`, sets the default, which shows that level and up.
+    /// So does the target `*`, like in the map form, so `*=info` is the same as `info`.
+    /// Blank strings and rules with an empty target, like `=warn`, are skipped.
     pub fn new<I, S>(targets: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: ToString,
     {
-        Self {
-            targets: targets
-                .into_iter()
-                .map(|s| s.to_string())
-                .filter_map(|s| {
-                    let mut iter = s.split('=');
-                    (iter.next()?.to_string(), parse_level(iter.next()?)).into()
-                })
-                .collect(),
+        let mut this = Self::default();
+        let mut key_only = vec![];
+        for s in targets.into_iter().map(|s| s.to_string()) {
+            if s.trim().is_empty() {
+                continue;
+            }
+            let mut iter = s.splitn(2, '=');
+            let target = iter.next().unwrap_or_default();
+            if let Some(excluded) = target.strip_prefix('!') {
+                this.exclusions.push(excluded.to_string());
+                continue;
+            }
+            let level = match iter.next() {
+                Some(..) if target.is_empty() => continue,
+                Some(level) if target == "*" => {
+                    this.default = Some(parse_level(level).unwrap_or(log::LevelFilter::Off));
+                    continue;
+                }
+                Some(level) => level,
+                None => {
+                    match parse_level(target) {
+                        Some(default) => this.default = Some(default),
+                        None => key_only.push(target.to_string()),
+                    }
+                    continue;
+                }
+            };
+            let level = parse_level(level).unwrap_or(log::LevelFilter::Off);
+            this.targets
+                .insert(target.to_string(), Rule::new(target, level));
+        }
+        // the default can come after the key-only targets
+        if let Some(level) = this.default.and_then(hidden_from) {
+            for target in key_only {
+                let rule = Rule::new(&target, level);
+                this.targets.entry(target).or_insert(rule);
+            }
+        }
+        this
+    }
+
+    /// Create a new filtered set from an iterator of strings, rejecting malformed rules
+    ///
+    /// Like [`new`](#method.new), but a rule without a `=` (that isn't a level), with an empty
+    /// target or with an unknown level is an error. Blank strings are still skipped, and
+    /// whitespace around the target and the level is trimmed, so `foo=info, bar=debug` works.
+    pub fn try_new<I, S>(targets: I) -> Result<Self, FilterParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let rules = targets
+            .into_iter()
+            .map(|s| s.to_string())
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| match s.split_once('=') {
+                Some((target, level)) => format!("{}={}", target.trim(), level.trim()),
+                None => s.trim().to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        for rule in &rules {
+            let (target, level) = match rule.split_once('=') {
+                Some((target, level)) => (target, Some(level)),
+                None => (rule.as_str(), None),
+            };
+            match (target.strip_prefix('!').unwrap_or(target), level) {
+                ("", _) => return Err(FilterParseError::EmptyTarget(rule.clone())),
+                (..) if target.starts_with('!') => {}
+                (target, None) if parse_level(target).is_none() => {
+                    return Err(FilterParseError::MissingLevel(rule.clone()))
+                }
+                (_, Some(level)) if parse_level(level).is_none() => {
+                    return Err(FilterParseError::UnknownLevel {
+                        rule: rule.clone(),
+                        level: level.to_string(),
+                    })
+                }
+                _ => {}
+            }
         }
+
+        Ok(Self::new(rules))
+    }
+
+    /** Create a new filtered set of regular expression rules
+
+    The format should be `pattern=level`, where the level is after the last `=`. A pattern
+    matches anywhere in the target unless it's anchored, so `::handler$` matches every target
+    ending in `::handler`. An invalid pattern or level is an error.
+
+    A regex rule is only used for targets that no plain or glob rule matches, and the first
+    matching regex rule wins. Exclusions and the default level can be added with
+    [`merge`](#method.merge). Regex rules aren't serialized.
+    */
+    #[cfg(feature = "regex")]
+    pub fn new_regex<I, S>(rules: I) -> Result<Self, FilterParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let mut this = Self::default();
+        for rule in rules.into_iter().map(|s| s.to_string()) {
+            if rule.trim().is_empty() {
+                continue;
+            }
+            let (pattern, level) = rule
+                .rsplit_once('=')
+                .ok_or_else(|| FilterParseError::MissingLevel(rule.clone()))?;
+            let level = parse_level(level).ok_or_else(|| FilterParseError::UnknownLevel {
+                rule: rule.clone(),
+                level: level.to_string(),
+            })?;
+            let regex =
+                regex::Regex::new(pattern).map_err(|err| FilterParseError::InvalidRegex {
+                    rule: rule.clone(),
+                    error: err.to_string(),
+                })?;
+            this.regexes.push((regex, level));
+        }
+        Ok(this)
     }
 
     /// Create a new filtered set from a string
     ///
-    /// The format should be `target1=level,target2=level`
+    /// The format should be `target1=level,target2=level`. A bare level sets the default, so
+    /// `info,tokio=warn` shows `Info` and up for every target but `tokio`, like `env_logger`.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(string: impl AsRef<str>) -> Self {
         Self::new(string.as_ref().split(","))
     }
 
+    /// Create a new filtered set from a string, rejecting malformed rules
+    ///
+    /// See [`try_new`](#method.try_new)
+    pub fn try_from_str(string: impl AsRef<str>) -> Result<Self, FilterParseError> {
+        Self::try_new(string.as_ref().split(","))
+    }
+
     /// Create a fitlered set from the environment, reading `RUST_LOG`
     pub fn from_env() -> Self {
         Self::from_env_key("RUST_LOG")
@@ -65,36 +295,1173 @@ impl Filtered {
     pub fn from_env_key(key: &str) -> Self {
         match std::env::var(key) {
             Ok(value) => Self::new(value.split(',')),
-            _ => Self {
-                targets: Default::default(),
-            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Create a filtered set from a file, with a `target=level` rule per line
+    ///
+    /// Blank lines are skipped, everything after a `#` is a comment and spaces around the `=`
+    /// are ignored
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(Self::from_lines(&data))
+    }
+
+    /// Create a filtered set from a file, rejecting malformed rules
+    ///
+    /// Like [`from_file`](#method.from_file), but the rules are parsed with
+    /// [`try_new`](#method.try_new). A malformed rule is an `InvalidData` error.
+    pub fn try_from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Self::try_from_lines(&data)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    fn from_lines(data: &str) -> Self {
+        Self::new(rules(data))
+    }
+
+    // like `from_lines`, but the rules must be well-formed
+    fn try_from_lines(data: &str) -> Result<Self, FilterParseError> {
+        Self::try_new(rules(data))
+    }
+
+    /** Also match the rules against the record's module path
+
+    Some libraries log with a target that isn't their module path, so `mycrate::internal=off`
+    wouldn't hide them. With this a record is hidden if either its target or its module path
+    hides it. Records without a module path are only matched on their target.
+    */
+    pub fn with_module_path(mut self) -> Self {
+        self.module_path = true;
+        self
+    }
+
+    /// Only match the rules against the record's target (the default)
+    pub fn without_module_path(mut self) -> Self {
+        self.module_path = false;
+        self
+    }
+
+    /** Add a rule for records logged from a source file
+
+    The pattern matches a file if its path components appear in the file's path, so
+    `vendor/parser.rs` matches `src/vendor/parser.rs` and `vendor` matches every file under a
+    `vendor` directory. The level works like a target rule's, and the most specific (longest)
+    matching pattern wins. A record is hidden if either its target or its file hides it, and
+    records without a file are only matched on their target. File rules aren't serialized.
+    */
+    pub fn by_file(
+        mut self,
+        pattern: impl Into<std::path::PathBuf>,
+        level: log::LevelFilter,
+    ) -> Self {
+        self.files.push((pattern.into(), level));
+        self
+    }
+
+    /// Match targets and exclusions ignoring ASCII case, this is off by default
+    ///
+    /// Regex rules aren't affected, they can use `(?i)` instead
+    pub fn case_insensitive(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /** Show this level and up for targets that don't match any rule
+
+    This mirrors a bare `RUST_LOG=info` in `env_logger`, so `with_default(Info)` shows `Info` and
+    hides `Debug` and `Trace` from targets without a more specific rule. Exclusions still hide
+    everything.
+    */
+    pub fn with_default(mut self, level: log::LevelFilter) -> Self {
+        self.default.replace(level);
+        self
+    }
+
+    /// Don't filter targets that don't match any rule (the default)
+    pub fn without_default(mut self) -> Self {
+        self.default.take();
+        self
+    }
+
+    /// Add a rule for this target, replacing any existing rule for it
+    ///
+    /// The target can be a glob, like in [`new`](#method.new)
+    pub fn insert(&mut self, target: impl Into<String>, level: log::LevelFilter) {
+        let target = target.into();
+        let rule = Rule::new(&target, level);
+        self.targets.insert(target, rule);
+    }
+
+    /// Remove the rule for this target, returning its level if it had one
+    pub fn remove(&mut self, target: &str) -> Option<log::LevelFilter> {
+        self.targets.remove(target).map(|rule| rule.level)
+    }
+
+    /** Layer another filtered set on top of this one
+
+    A rule in `other` replaces the rule for the same target here, and its other rules and
+    exclusions are added. The default level of `other` replaces this one if it has one,
+    otherwise this default is kept. Exclusions here are kept even if `other` has a rule for
+    that target, as they always win. Module path matching and ignoring case are used if either
+    set uses them.
+    */
+    pub fn merge(&mut self, other: Filtered) {
+        self.targets.extend(other.targets);
+        for excluded in other.exclusions {
+            if !self.exclusions.contains(&excluded) {
+                self.exclusions.push(excluded);
+            }
         }
+        self.default = other.default.or(self.default);
+        #[cfg(feature = "regex")]
+        self.regexes.extend(other.regexes);
+        self.files.extend(other.files);
+        self.module_path |= other.module_path;
+        self.ignore_case |= other.ignore_case;
     }
 
     /// Returns an iterator of the `target,level` pairs
+    ///
+    /// This doesn't include the exclusions, see [`excluded`](#method.excluded)
     pub fn filtered(&self) -> impl Iterator<Item = (&String, &log::LevelFilter)> {
-        self.targets.iter()
+        self.targets
+            .iter()
+            .map(|(target, rule)| (target, &rule.level))
+    }
+
+    /// Returns an iterator of the excluded targets, without their leading `!`
+    pub fn excluded(&self) -> impl Iterator<Item = &String> {
+        self.exclusions.iter()
+    }
+
+    /** Get the most verbose level that is printed for this target
+
+    A rule `target=level` hides that level and anything more verbose, so `foo=debug` prints
+    `Info` and up for `foo`. Only the most specific matching rule is used: the one with the
+    most `::` segments, then a plain target over a glob, then the longest. So with
+    `tokio=warn,tokio::io=trace` the target `tokio::io::read` only uses `tokio::io=trace`. A
+    rule with an unknown level hides everything, which gives `Off`. A target without a matching
+    rule gives the default level, and without a default it's not filtered, which gives `Trace`.
+    An excluded target gives `Off`.
+    */
+    pub fn level_for(&self, target: &str) -> log::LevelFilter {
+        printed(self.effective_level(target))
+    }
+
+    /** Get the level of the rule that governs this target
+
+    This is the level as it was written, `foo=debug` gives `Debug` for `foo`, chosen the same
+    way as [`level_for`](#method.level_for). An excluded target gives `Off`. A target without a
+    matching rule gives the rule the default level amounts to, so a default of `Info` gives
+    `Debug`, and without a default (or with a default of `Trace`) it gives `None`.
+    */
+    pub fn effective_level(&self, target: &str) -> Option<log::LevelFilter> {
+        if self.is_excluded(target) {
+            return Some(log::LevelFilter::Off);
+        }
+        self.matching(target)
+    }
+
+    fn is_excluded(&self, input: &str) -> bool {
+        self.exclusions
+            .iter()
+            .any(|excluded| in_module(input, excluded, self.ignore_case))
+    }
+
+    fn matching(&self, input: &str) -> Option<log::LevelFilter> {
+        self.targets
+            .iter()
+            .filter(|(k, rule)| rule.matches(k, input, self.ignore_case))
+            // the target itself breaks ties, so the choice doesn't depend on the map's order
+            .max_by_key(|(k, rule)| (k.split("::").count(), rule.glob.is_none(), k.len(), *k))
+            .map(|(_, rule)| rule.level)
+            .or_else(|| self.matching_regex(input))
+            .or_else(|| self.default.and_then(hidden_from))
+    }
+
+    #[cfg(feature = "regex")]
+    fn matching_regex(&self, input: &str) -> Option<log::LevelFilter> {
+        self.regexes
+            .iter()
+            .find(|(regex, _)| regex.is_match(input))
+            .map(|&(_, level)| level)
+    }
+
+    #[cfg(not(feature = "regex"))]
+    #[inline]
+    fn matching_regex(&self, _input: &str) -> Option<log::LevelFilter> {
+        None
     }
 
     #[inline]
     pub(crate) fn apply(&self, input: &str, level: log::Level) -> bool {
-        self.targets.iter().any(|(k, v)| {
-            if !input.starts_with(k) || !input.contains("::") && k != input {
-                return false;
+        level > self.level_for(input)
+    }
+
+    fn matching_file(&self, file: &str) -> Option<log::LevelFilter> {
+        let file = std::path::Path::new(file).components().collect::<Vec<_>>();
+        self.files
+            .iter()
+            .filter(|(pattern, _)| {
+                let pattern = pattern.components().collect::<Vec<_>>();
+                !pattern.is_empty() && file.windows(pattern.len()).any(|w| w == &*pattern)
+            })
+            .max_by_key(|(pattern, _)| pattern.components().count())
+            .map(|&(_, level)| level)
+    }
+
+    // whether the record is hidden by its target, its file, or by its module path if that's
+    // enabled
+    #[inline]
+    pub(crate) fn apply_record(&self, record: &log::Record) -> bool {
+        if self.apply(record.target(), record.level()) {
+            return true;
+        }
+        if let Some(level) = record.file().and_then(|file| self.matching_file(file)) {
+            if record.level() > printed(Some(level)) {
+                return true;
+            }
+        }
+        match record.module_path() {
+            Some(module_path) if self.module_path => self.apply(module_path, record.level()),
+            _ => false,
+        }
+    }
+}
+
+// the most verbose level printed by the matching rule, if any
+fn printed(rule: Option<log::LevelFilter>) -> log::LevelFilter {
+    use log::LevelFilter::*;
+    match rule {
+        Some(Off) | Some(Error) => Off,
+        Some(Warn) => Error,
+        Some(Info) => Warn,
+        Some(Debug) => Info,
+        Some(Trace) => Debug,
+        None => Trace,
+    }
+}
+
+// the rule level that prints `shown` and up, the inverse of `printed`
+fn hidden_from(shown: log::LevelFilter) -> Option<log::LevelFilter> {
+    use log::LevelFilter::*;
+    match shown {
+        Off => Some(Off),
+        Error => Some(Warn),
+        Warn => Some(Info),
+        Info => Some(Debug),
+        Debug => Some(Trace),
+        Trace => None,
+    }
+}
+
+// the `target=level` rules of a file, without comments, blank lines or spaces around the `=`
+fn rules(data: &str) -> impl Iterator<Item = String> + '_ {
+    data.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split('=').map(str::trim).collect::<Vec<_>>().join("="))
+}
+
+/// Parses leniently, like [`Filtered::from_str`](./struct.Filtered.html#method.from_str)
+impl std::str::FromStr for Filtered {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_str(s))
+    }
+}
+
+impl From<&str> for Filtered {
+    fn from(s: &str) -> Self {
+        Self::from_str(s)
+    }
+}
+
+/// Writes the rules in the form [`Filtered::from_str`](./struct.Filtered.html#method.from_str)
+/// reads, like `info,hyper=debug,tokio=trace,!mio`
+///
+/// The default level comes first, then the rules sorted by target, then the exclusions. Regex
+/// and file rules, and the matching options, aren't written.
+impl std::fmt::Display for Filtered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut targets = self.targets.iter().collect::<Vec<_>>();
+        targets.sort_unstable_by_key(|&(k, _)| k);
+        let mut exclusions = self.exclusions.iter().collect::<Vec<_>>();
+        exclusions.sort_unstable();
+
+        let default = self.default.map(|level| level_name(level).to_string());
+        let targets = targets
+            .into_iter()
+            .map(|(target, rule)| format!("{}={}", target, level_name(rule.level)));
+        let exclusions = exclusions.into_iter().map(|target| format!("!{}", target));
+
+        for (i, rule) in default
+            .into_iter()
+            .chain(targets)
+            .chain(exclusions)
+            .enumerate()
+        {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(&rule)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts either a map of `target: level` or the `target=level,target=level` string form
+///
+/// Unknown levels in the map form are an error. A `*` target is the default level in both forms,
+/// and a target starting with `!` is an exclusion.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Filtered {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Filtered;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map of `target: level` or a `target=level` string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Filtered::try_from_str(v).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut filtered = Filtered::default();
+                while let Some((target, level)) = map.next_entry::<String, String>()? {
+                    let level = parse_level(&level).ok_or_else(|| {
+                        serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Str(&level),
+                            &"a log level",
+                        )
+                    })?;
+                    match target.strip_prefix('!') {
+                        Some(excluded) => filtered.exclusions.push(excluded.to_string()),
+                        None if target == "*" => filtered.default = Some(level),
+                        None => {
+                            let rule = Rule::new(&target, level);
+                            filtered.targets.insert(target, rule);
+                        }
+                    }
+                }
+                Ok(filtered)
             }
-            level >= *v
-        })
+        }
+
+        deserializer.deserialize_any(Visitor)
     }
 }
 
-#[inline]
-fn parse_level(s: &str) -> log::LevelFilter {
-    match s {
-        s if s.eq_ignore_ascii_case("trace") => log::LevelFilter::Trace,
-        s if s.eq_ignore_ascii_case("debug") => log::LevelFilter::Debug,
-        s if s.eq_ignore_ascii_case("info") => log::LevelFilter::Info,
-        s if s.eq_ignore_ascii_case("warn") => log::LevelFilter::Warn,
-        s if s.eq_ignore_ascii_case("error") => log::LevelFilter::Error,
-        _ => log::LevelFilter::Off,
+/// Serializes as a map of `target: level`, sorted by target, with lowercase levels
+///
+/// Exclusions are written last, as `!target: off`. The default level is written as `*`, which is
+/// read back as the default.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Filtered {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap as _;
+        let mut targets = self.targets.iter().collect::<Vec<_>>();
+        targets.sort_unstable_by_key(|&(k, _)| k);
+
+        let len = targets.len() + self.exclusions.len() + self.default.iter().count();
+        let mut map = serializer.serialize_map(Some(len))?;
+        for (target, rule) in targets {
+            map.serialize_entry(target, level_name(rule.level))?;
+        }
+        if let Some(default) = self.default {
+            map.serialize_entry("*", level_name(default))?;
+        }
+        for excluded in &self.exclusions {
+            map.serialize_entry(&format!("!{}", excluded), "off")?;
+        }
+        map.end()
+    }
+}
+
+fn level_name(level: log::LevelFilter) -> &'static str {
+    match level {
+        log::LevelFilter::Off => "off",
+        log::LevelFilter::Error => "error",
+        log::LevelFilter::Warn => "warn",
+        log::LevelFilter::Info => "info",
+        log::LevelFilter::Debug => "debug",
+        log::LevelFilter::Trace => "trace",
+    }
+}
+
+/// A malformed rule, found by [`Filtered::try_new`](./struct.Filtered.html#method.try_new)
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FilterParseError {
+    /// The rule has no `=` and isn't a level
+    MissingLevel(String),
+    /// The rule has no target before the `=`, or nothing after a `!`
+    EmptyTarget(String),
+    /// The level after the `=` isn't known
+    UnknownLevel { rule: String, level: String },
+    /// The pattern isn't a valid regular expression
+    #[cfg(feature = "regex")]
+    InvalidRegex { rule: String, error: String },
+}
+
+impl std::fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLevel(rule) => write!(f, "invalid rule: `{}` has no level", rule),
+            Self::EmptyTarget(rule) => write!(f, "invalid rule: `{}` has no target", rule),
+            Self::UnknownLevel { rule, level } => write!(
+                f,
+                "invalid rule: `{}` has an unknown level: `{}`",
+                rule, level
+            ),
+            #[cfg(feature = "regex")]
+            Self::InvalidRegex { rule, error } => {
+                write!(
+                    f,
+                    "invalid rule: `{}` has an invalid pattern: {}",
+                    rule, error
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+/// An error returned when a level could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLevelError(pub(crate) String);
+
+impl std::fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown log level: `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+/// Parse a level name into a `log::LevelFilter`
+///
+/// The accepted names, ignoring case, are:
+///
+/// | Level | Names                   |
+/// | --    | --                      |
+/// | Trace | `trace`, `t`            |
+/// | Debug | `debug`, `dbg`, `d`     |
+/// | Info  | `info`, `i`             |
+/// | Warn  | `warn`, `warning`, `w`  |
+/// | Error | `error`, `err`, `e`     |
+/// | Off   | `off`                   |
+///
+/// Returns `None` for anything else
+pub fn parse_level(s: &str) -> Option<log::LevelFilter> {
+    const NAMES: &[(&str, log::LevelFilter)] = &[
+        ("trace", log::LevelFilter::Trace),
+        ("t", log::LevelFilter::Trace),
+        ("debug", log::LevelFilter::Debug),
+        ("dbg", log::LevelFilter::Debug),
+        ("d", log::LevelFilter::Debug),
+        ("info", log::LevelFilter::Info),
+        ("i", log::LevelFilter::Info),
+        ("warn", log::LevelFilter::Warn),
+        ("warning", log::LevelFilter::Warn),
+        ("w", log::LevelFilter::Warn),
+        ("error", log::LevelFilter::Error),
+        ("err", log::LevelFilter::Error),
+        ("e", log::LevelFilter::Error),
+        ("off", log::LevelFilter::Off),
+    ];
+
+    NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map(|&(_, level)| level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed() {
+        let filtered = Filtered::new(["", " ", "a", "b=", "c=info=debug", "=warn", "d=debug"]);
+        let mut rules = filtered.filtered().collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                (&"b".to_string(), &log::LevelFilter::Off),
+                (&"c".to_string(), &log::LevelFilter::Off),
+                (&"d".to_string(), &log::LevelFilter::Debug),
+            ]
+        );
+        assert_eq!(filtered.level_for("a"), log::LevelFilter::Trace);
+
+        let filtered = Filtered::new(["a", "info", "b=debug"]);
+        assert_eq!(filtered.filtered().count(), 2);
+        assert_eq!(filtered.level_for("a"), log::LevelFilter::Info);
+        assert_eq!(filtered.level_for("b"), log::LevelFilter::Info);
+    }
+
+    #[test]
+    fn try_from_str() {
+        let filtered = Filtered::try_from_str("info,tokio=trace,!mio,,hyper::*=w").unwrap();
+        assert_eq!(filtered.filtered().count(), 2);
+        assert_eq!(filtered.level_for("other"), log::LevelFilter::Info);
+
+        let filtered = Filtered::try_from_str("foo=info, bar = debug , warn").unwrap();
+        let mut rules = filtered.filtered().collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                (&"bar".to_string(), &log::LevelFilter::Debug),
+                (&"foo".to_string(), &log::LevelFilter::Info),
+            ]
+        );
+        assert_eq!(filtered.level_for("other"), log::LevelFilter::Warn);
+
+        let filtered = Filtered::try_from_str("*=debug,tokio=warn").unwrap();
+        assert_eq!(filtered.filtered().count(), 1);
+        assert_eq!(filtered.level_for("other"), log::LevelFilter::Debug);
+        assert_eq!(filtered.to_string(), "debug,tokio=warn");
+
+        for (input, expected) in [
+            ("tokoi", FilterParseError::MissingLevel("tokoi".into())),
+            ("=warn", FilterParseError::EmptyTarget("=warn".into())),
+            ("!", FilterParseError::EmptyTarget("!".into())),
+            (
+                "tokio=tracce",
+                FilterParseError::UnknownLevel {
+                    rule: "tokio=tracce".into(),
+                    level: "tracce".into(),
+                },
+            ),
+            (
+                "a=info,b=",
+                FilterParseError::UnknownLevel {
+                    rule: "b=".into(),
+                    level: "".into(),
+                },
+            ),
+        ] {
+            assert_eq!(
+                Filtered::try_from_str(input).err(),
+                Some(expected),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(
+            Filtered::try_new(["a=bogus"])
+                .err()
+                .map(|err| err.to_string()),
+            Some("invalid rule: `a=bogus` has an unknown level: `bogus`".to_string())
+        );
+    }
+
+    #[test]
+    fn display() {
+        let input = "tokio=TRACE,!mio,hyper::*=d,warn,baz=off,!a";
+        let filtered = Filtered::from_str(input);
+        let display = filtered.to_string();
+        assert_eq!(display, "warn,baz=off,hyper::*=debug,tokio=trace,!a,!mio");
+
+        let round_trip = Filtered::from_str(&display);
+        assert_eq!(round_trip.to_string(), display);
+
+        let sorted = |filtered: &Filtered| {
+            let mut rules = filtered
+                .filtered()
+                .map(|(k, &v)| (k.clone(), v))
+                .collect::<Vec<_>>();
+            rules.sort();
+            rules
+        };
+        assert_eq!(sorted(&round_trip), sorted(&filtered));
+        assert_eq!(round_trip.default, filtered.default);
+
+        assert_eq!(Filtered::default().to_string(), "");
+    }
+
+    #[test]
+    fn from_file() {
+        let data = "
+            # noisy crates
+            hyper=debug
+            tokio::io = trace # very noisy
+
+            mio=warn
+        ";
+        let filtered = Filtered::from_lines(data);
+        let mut rules = filtered.filtered().collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                (&"hyper".to_string(), &log::LevelFilter::Debug),
+                (&"mio".to_string(), &log::LevelFilter::Warn),
+                (&"tokio::io".to_string(), &log::LevelFilter::Trace),
+            ]
+        );
+
+        assert!(Filtered::from_file("does/not/exist.conf").is_err());
+    }
+
+    #[test]
+    fn try_from_file() {
+        let path = std::env::temp_dir().join(format!(
+            "arbitrary_logger-try_from_file-{}.conf",
+            std::process::id()
+        ));
+
+        std::fs::write(&path, "hyper = debug # noisy\nmio=warn\n").unwrap();
+        let filtered = Filtered::try_from_file(&path).unwrap();
+        assert_eq!(filtered.filtered().count(), 2);
+
+        std::fs::write(&path, "hyper=debug\nmio=verbose\n").unwrap();
+        let err = Filtered::try_from_file(&path).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(Filtered::from_file(&path).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Filtered::try_from_file(&path).err().unwrap().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn level_for() {
+        let filtered = Filtered::new(["a=warn", "a::b=trace", "c=bogus"]);
+        for (target, expected) in &[
+            ("a", log::LevelFilter::Error),
+            ("a::c", log::LevelFilter::Error),
+            ("a::b", log::LevelFilter::Debug),
+            ("a::b::c", log::LevelFilter::Debug),
+            ("c", log::LevelFilter::Off),
+            ("d", log::LevelFilter::Trace),
+            ("ab", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+
+        // never disagrees with `apply`
+        for target in &["a", "a::c", "a::b", "a::b::c", "c", "d", "ab"] {
+            for level in log::Level::iter() {
+                assert_eq!(
+                    filtered.apply(target, level),
+                    level > filtered.level_for(target),
+                    "{}: {:?}",
+                    target,
+                    level
+                );
+            }
+        }
+
+        assert_eq!(Filtered::default().level_for("a"), log::LevelFilter::Trace);
+    }
+
+    #[test]
+    fn segment_boundaries() {
+        let filtered = Filtered::new(["mio=off"]);
+        for (target, expected) in &[
+            ("mio", log::LevelFilter::Off),
+            ("mio::uds", log::LevelFilter::Off),
+            ("mionext", log::LevelFilter::Trace),
+            ("mionext::inner", log::LevelFilter::Trace),
+            ("mio_extras::net", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let filtered = Filtered::new(["MyCrate::Net=debug", "Other::*=off", "!Mio"]);
+        assert_eq!(filtered.level_for("mycrate::net"), log::LevelFilter::Trace);
+
+        let filtered = filtered.case_insensitive(true);
+        for (target, expected) in &[
+            ("mycrate::net", log::LevelFilter::Info),
+            ("MYCRATE::NET::tcp", log::LevelFilter::Info),
+            ("other::inner", log::LevelFilter::Off),
+            ("mio::uds", log::LevelFilter::Off),
+            ("mycrate::netx", log::LevelFilter::Trace),
+            ("\u{e9}\u{e9}", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+
+        let filtered = filtered.case_insensitive(false);
+        assert_eq!(filtered.level_for("mycrate::net"), log::LevelFilter::Trace);
+    }
+
+    #[test]
+    fn most_specific() {
+        let filtered = Filtered::new(["tokio=warn", "tokio::io=trace"]);
+        assert!(!filtered.apply("tokio::io::read", log::Level::Debug));
+        assert!(filtered.apply("tokio::io::read", log::Level::Trace));
+        assert!(filtered.apply("tokio::net", log::Level::Debug));
+
+        // more segments win over a longer target, and a plain target wins over a glob
+        let filtered = Filtered::new([
+            "tokio::*=trace",
+            "tokio::io=warn",
+            "a::b=info",
+            "abcdef=off",
+        ]);
+        assert_eq!(filtered.level_for("tokio::io"), log::LevelFilter::Error);
+        assert_eq!(
+            filtered.level_for("tokio::io::read"),
+            log::LevelFilter::Error
+        );
+        assert_eq!(filtered.level_for("tokio::net"), log::LevelFilter::Debug);
+
+        // ties are decided the same way every time
+        for _ in 0..10 {
+            let filtered = Filtered::new(["*::ab=warn", "ab::*=trace"]);
+            assert_eq!(filtered.level_for("ab::ab"), log::LevelFilter::Debug);
+        }
+    }
+
+    #[test]
+    fn glob() {
+        let filtered = Filtered::new(["tokio::*=trace", "tokio::net=warn", "*::io=info"]);
+        for (target, expected) in &[
+            ("tokio", log::LevelFilter::Trace),
+            ("tokio::sync", log::LevelFilter::Debug),
+            ("tokio::sync::mpsc", log::LevelFilter::Debug),
+            ("tokio::net", log::LevelFilter::Error),
+            ("tokio::net::tcp", log::LevelFilter::Error),
+            ("tokio::io", log::LevelFilter::Debug),
+            ("mio::io", log::LevelFilter::Warn),
+            ("mio::io::unix", log::LevelFilter::Warn),
+            ("io", log::LevelFilter::Trace),
+            ("tokiox::sync", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+
+        let filtered = Filtered::new(["hyper::*::client=debug"]);
+        for (target, expected) in &[
+            ("hyper::client", log::LevelFilter::Trace),
+            ("hyper::proto::client", log::LevelFilter::Info),
+            ("hyper::proto::h1::client", log::LevelFilter::Info),
+            ("hyper::proto::h1::client::conn", log::LevelFilter::Info),
+            ("hyper::proto::server", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+
+        // only a whole `*` segment is a glob
+        let filtered = Filtered::new(["foo*=off", "a?[b]=off"]);
+        for (target, expected) in &[
+            ("foo*", log::LevelFilter::Off),
+            ("foo*::bar", log::LevelFilter::Off),
+            ("foobar", log::LevelFilter::Trace),
+            ("foo::bar", log::LevelFilter::Trace),
+            ("a?[b]", log::LevelFilter::Off),
+            ("ab", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+    }
+
+    #[test]
+    fn default_level() {
+        let filtered = Filtered::from_str("info,mio=trace");
+        assert_eq!(filtered.level_for("foo::bar"), log::LevelFilter::Info);
+        assert!(!filtered.apply("foo::bar", log::Level::Info));
+        assert!(filtered.apply("foo::bar", log::Level::Debug));
+        assert!(filtered.apply("foo::bar", log::Level::Trace));
+        assert_eq!(filtered.level_for("mio::net"), log::LevelFilter::Debug);
+        assert_eq!(filtered.filtered().count(), 1);
+
+        let filtered = Filtered::new(["!foo::baz"]).with_default(log::LevelFilter::Debug);
+        assert_eq!(filtered.level_for("foo::bar"), log::LevelFilter::Debug);
+        assert_eq!(filtered.level_for("foo::baz"), log::LevelFilter::Off);
+
+        let filtered = filtered.with_default(log::LevelFilter::Info);
+        assert!(!filtered.apply("foo::bar", log::Level::Info));
+        assert!(filtered.apply("foo::bar", log::Level::Debug));
+
+        let filtered = filtered.without_default();
+        assert_eq!(filtered.level_for("foo::bar"), log::LevelFilter::Trace);
+
+        assert!(Filtered::try_from_lines("info\nmio=trace").is_ok());
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut filtered = Filtered::from_str("foo=warn");
+        filtered.insert("bar", log::LevelFilter::Info);
+        filtered.insert("foo", log::LevelFilter::Trace);
+        filtered.insert("baz::*", log::LevelFilter::Off);
+
+        let mut rules = filtered.filtered().collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                (&"bar".to_string(), &log::LevelFilter::Info),
+                (&"baz::*".to_string(), &log::LevelFilter::Off),
+                (&"foo".to_string(), &log::LevelFilter::Trace),
+            ]
+        );
+        assert_eq!(filtered.level_for("baz::io"), log::LevelFilter::Off);
+
+        assert_eq!(filtered.remove("foo"), Some(log::LevelFilter::Trace));
+        assert_eq!(filtered.remove("foo"), None);
+        assert_eq!(filtered.remove("baz::*"), Some(log::LevelFilter::Off));
+        assert_eq!(
+            filtered.filtered().collect::<Vec<_>>(),
+            vec![(&"bar".to_string(), &log::LevelFilter::Info)]
+        );
+        assert_eq!(filtered.level_for("baz::io"), log::LevelFilter::Trace);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn regex() {
+        let filtered = Filtered::new_regex(["::handler$=debug", "^api=warn", "db=off"]).unwrap();
+        for (target, expected) in &[
+            ("app::users::handler", log::LevelFilter::Info),
+            ("app::handler::inner", log::LevelFilter::Trace),
+            ("api::v1", log::LevelFilter::Error),
+            ("app::api", log::LevelFilter::Trace),
+            ("app::db::pool", log::LevelFilter::Off),
+            ("other", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+
+        // plain rules win, then the first matching regex
+        let mut filtered = Filtered::new_regex(["a=trace", "b=warn", "x{2}=off"]).unwrap();
+        filtered.merge(Filtered::from_str("app::b=info"));
+        assert_eq!(filtered.level_for("app::b"), log::LevelFilter::Warn);
+        assert_eq!(filtered.level_for("cab"), log::LevelFilter::Debug);
+        assert_eq!(filtered.level_for("xx"), log::LevelFilter::Off);
+
+        assert!(matches!(
+            Filtered::new_regex(["(unclosed=info"]).err(),
+            Some(FilterParseError::InvalidRegex { .. })
+        ));
+        assert!(matches!(
+            Filtered::new_regex(["handler"]).err(),
+            Some(FilterParseError::MissingLevel(..))
+        ));
+        assert!(matches!(
+            Filtered::new_regex(["handler=tracce"]).err(),
+            Some(FilterParseError::UnknownLevel { .. })
+        ));
+    }
+
+    #[test]
+    fn merge() {
+        let mut filtered = Filtered::from_str("foo=warn,baz=off,info");
+        filtered.merge(Filtered::from_str("foo=trace,bar=info,!quux"));
+
+        let mut rules = filtered.filtered().collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec![
+                (&"bar".to_string(), &log::LevelFilter::Info),
+                (&"baz".to_string(), &log::LevelFilter::Off),
+                (&"foo".to_string(), &log::LevelFilter::Trace),
+            ]
+        );
+        assert_eq!(filtered.excluded().collect::<Vec<_>>(), vec!["quux"]);
+        assert_eq!(filtered.level_for("other"), log::LevelFilter::Info);
+
+        filtered.merge(Filtered::from_str("debug,!quux"));
+        assert_eq!(filtered.level_for("other"), log::LevelFilter::Debug);
+        assert_eq!(filtered.excluded().count(), 1);
+    }
+
+    #[test]
+    fn global_level_from_env() {
+        std::env::set_var("ARBITRARY_LOGGER_TEST_GLOBAL_LEVEL", "info,tokio=warn,");
+        let filtered = Filtered::from_env_key("ARBITRARY_LOGGER_TEST_GLOBAL_LEVEL");
+        assert_eq!(filtered.level_for("hyper::client"), log::LevelFilter::Info);
+        assert_eq!(filtered.level_for("tokio::net"), log::LevelFilter::Error);
+        assert_eq!(
+            filtered.filtered().collect::<Vec<_>>(),
+            vec![(&"tokio".to_string(), &log::LevelFilter::Warn)]
+        );
+    }
+
+    #[test]
+    fn exclusions() {
+        let filtered = Filtered::from_str("tokio=trace,!tokio::io,!mio");
+        for (target, expected) in &[
+            ("tokio", log::LevelFilter::Debug),
+            ("tokio::net", log::LevelFilter::Debug),
+            ("tokio::io", log::LevelFilter::Off),
+            ("tokio::io::read", log::LevelFilter::Off),
+            ("tokio::iox", log::LevelFilter::Debug),
+            ("mio", log::LevelFilter::Off),
+            ("mio::uds", log::LevelFilter::Off),
+            ("mio_extras", log::LevelFilter::Trace),
+        ] {
+            assert_eq!(filtered.level_for(target), *expected, "{}", target);
+        }
+        assert!(filtered.apply("tokio::io", log::Level::Error));
+        assert!(!filtered.apply("tokio::net", log::Level::Debug));
+
+        let filtered = Filtered::from_str("mio=trace,!mio::uds=trace");
+        assert_eq!(filtered.level_for("mio::net"), log::LevelFilter::Debug);
+        assert_eq!(filtered.level_for("mio::uds"), log::LevelFilter::Off);
+        assert_eq!(filtered.excluded().collect::<Vec<_>>(), vec!["mio::uds"]);
+        assert_eq!(filtered.filtered().count(), 1);
+
+        assert!(Filtered::try_from_lines("!mio\ntokio=trace").is_ok());
+    }
+
+    #[test]
+    fn effective_level() {
+        let filtered = Filtered::new(["a=warn", "a::b=trace", "c=bogus", "!e", "f::*=info"]);
+        for (target, expected) in &[
+            ("a", Some(log::LevelFilter::Warn)),
+            ("a::c", Some(log::LevelFilter::Warn)),
+            ("a::b", Some(log::LevelFilter::Trace)),
+            ("a::b::c", Some(log::LevelFilter::Trace)),
+            ("c", Some(log::LevelFilter::Off)),
+            ("d", None),
+            ("ab", None),
+            ("e::f", Some(log::LevelFilter::Off)),
+            ("f::g", Some(log::LevelFilter::Info)),
+        ] {
+            assert_eq!(filtered.effective_level(target), *expected, "{}", target);
+        }
+
+        // never disagrees with `apply`
+        for target in &[
+            "a", "a::c", "a::b", "a::b::c", "c", "d", "ab", "e::f", "f::g",
+        ] {
+            for level in log::Level::iter() {
+                let hidden = match filtered.effective_level(target) {
+                    Some(rule) => level >= rule,
+                    None => false,
+                };
+                assert_eq!(
+                    filtered.apply(target, level),
+                    hidden,
+                    "{}: {:?}",
+                    target,
+                    level
+                );
+            }
+        }
+
+        let filtered = filtered.with_default(log::LevelFilter::Debug);
+        assert_eq!(filtered.effective_level("d"), Some(log::LevelFilter::Trace));
+        let filtered = filtered.with_default(log::LevelFilter::Trace);
+        assert_eq!(filtered.effective_level("d"), None);
+    }
+
+    #[test]
+    fn module_path() {
+        let hidden = |filtered: &Filtered, target: &str, module_path: Option<&str>| {
+            filtered.apply_record(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target(target)
+                    .module_path(module_path)
+                    .build(),
+            )
+        };
+
+        let filtered = Filtered::new(["mycrate::internal=off"]);
+        assert!(hidden(&filtered, "mycrate::internal", None));
+        assert!(!hidden(&filtered, "events", Some("mycrate::internal::db")));
+
+        let filtered = filtered.with_module_path();
+        assert!(hidden(&filtered, "events", Some("mycrate::internal::db")));
+        assert!(hidden(&filtered, "mycrate::internal", Some("other")));
+        assert!(!hidden(&filtered, "events", Some("mycrate::public")));
+        assert!(!hidden(&filtered, "events", None));
+
+        assert!(!hidden(
+            &filtered.without_module_path(),
+            "events",
+            Some("mycrate::internal")
+        ));
+    }
+
+    #[test]
+    fn by_file() {
+        let hidden = |filtered: &Filtered, level: log::Level, file: Option<&str>| {
+            filtered.apply_record(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(level)
+                    .target("mycrate")
+                    .file(file)
+                    .build(),
+            )
+        };
+
+        let filtered = Filtered::default()
+            .by_file("vendor", log::LevelFilter::Warn)
+            .by_file("vendor/parser.rs", log::LevelFilter::Off);
+        assert!(hidden(
+            &filtered,
+            log::Level::Warn,
+            Some("src/vendor/lexer.rs")
+        ));
+        assert!(!hidden(
+            &filtered,
+            log::Level::Error,
+            Some("src/vendor/lexer.rs")
+        ));
+        assert!(hidden(
+            &filtered,
+            log::Level::Error,
+            Some("src/vendor/parser.rs")
+        ));
+        assert!(hidden(
+            &filtered,
+            log::Level::Info,
+            Some("src/vendor/parser.rs")
+        ));
+        assert!(!hidden(
+            &filtered,
+            log::Level::Trace,
+            Some("src/vendored/parser.rs")
+        ));
+        assert!(!hidden(&filtered, log::Level::Trace, Some("src/main.rs")));
+        assert!(!hidden(&filtered, log::Level::Trace, None));
+
+        let filtered = Filtered::new(["mycrate=info"]).by_file("main.rs", log::LevelFilter::Trace);
+        assert!(hidden(&filtered, log::Level::Info, Some("src/main.rs")));
+    }
+
+    #[test]
+    fn parse_level_names() {
+        for (input, expected) in &[
+            ("trace", log::LevelFilter::Trace),
+            ("Debug", log::LevelFilter::Debug),
+            ("INFO", log::LevelFilter::Info),
+            ("warn", log::LevelFilter::Warn),
+            ("error", log::LevelFilter::Error),
+            ("off", log::LevelFilter::Off),
+        ] {
+            assert_eq!(parse_level(input), Some(*expected), "{}", input);
+        }
+
+        for input in &["", "verbose", "tracce", " info", "o", "inf"] {
+            assert_eq!(parse_level(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_level_abbreviations() {
+        for (inputs, expected) in &[
+            (&["t", "T", "trace"][..], log::LevelFilter::Trace),
+            (&["d", "dbg", "DBG"][..], log::LevelFilter::Debug),
+            (&["i", "I"][..], log::LevelFilter::Info),
+            (&["w", "warning", "Warning"][..], log::LevelFilter::Warn),
+            (&["e", "err", "ERR"][..], log::LevelFilter::Error),
+        ] {
+            for input in *inputs {
+                assert_eq!(parse_level(input), Some(*expected), "{}", input);
+            }
+        }
+
+        let filtered = Filtered::from_str("foo=w,bar=err,baz=dbg");
+        assert_eq!(filtered.targets["foo"].level, log::LevelFilter::Warn);
+        assert_eq!(filtered.targets["bar"].level, log::LevelFilter::Error);
+        assert_eq!(filtered.targets["baz"].level, log::LevelFilter::Debug);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize() {
+        use serde::de::{value, Deserialize, IntoDeserializer};
+
+        let de: value::StrDeserializer<value::Error> =
+            "foo::bar=trace,baz=debug".into_deserializer();
+        let filtered = Filtered::deserialize(de).unwrap();
+        assert_eq!(filtered.targets["foo::bar"].level, log::LevelFilter::Trace);
+        assert_eq!(filtered.targets["baz"].level, log::LevelFilter::Debug);
+
+        let de: value::StrDeserializer<value::Error> = "foo=verbse".into_deserializer();
+        assert!(Filtered::deserialize(de).is_err());
+
+        let de = value::MapDeserializer::<_, value::Error>::new(
+            vec![("foo::bar", "TRACE"), ("baz", "debug")].into_iter(),
+        );
+        let filtered = Filtered::deserialize(de).unwrap();
+        assert_eq!(filtered.targets["foo::bar"].level, log::LevelFilter::Trace);
+        assert_eq!(filtered.targets["baz"].level, log::LevelFilter::Debug);
+
+        let de = value::MapDeserializer::<_, value::Error>::new(
+            vec![("foo::bar", "tracce")].into_iter(),
+        );
+        assert!(Filtered::deserialize(de).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn round_trip() {
+        let filtered = Filtered::from_str("warn,foo::bar=trace,baz=DEBUG,hyper::*=off,!mio");
+        let json = serde_json::to_string(&filtered).unwrap();
+        let round_trip: Filtered = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&round_trip).unwrap(), json);
+
+        for target in &[
+            "foo::bar",
+            "foo::baz",
+            "baz",
+            "hyper::client",
+            "mio",
+            "other",
+        ] {
+            assert_eq!(
+                round_trip.level_for(target),
+                filtered.level_for(target),
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialize() {
+        let filtered = Filtered::from_str("foo::bar=trace,baz=DEBUG,quux=off,!mio");
+        assert_eq!(
+            serde_json::to_string(&filtered).unwrap(),
+            r#"{"baz":"debug","foo::bar":"trace","quux":"off","!mio":"off"}"#
+        );
+
+        let filtered = Filtered::from_str("warn,foo=trace");
+        assert_eq!(
+            serde_json::to_string(&filtered).unwrap(),
+            r#"{"foo":"trace","*":"warn"}"#
+        );
     }
 }
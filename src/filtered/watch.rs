@@ -0,0 +1,122 @@
+use super::Filtered;
+
+use notify::Watcher as _;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Editors often write a file in several steps, wait for them to settle before reloading
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/** Watches a filter file, see [`watch_file`](./fn.watch_file.html)
+
+The file stops being watched when this is dropped.
+*/
+pub struct FilterWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/** Reload the installed logger's filters whenever this file changes
+
+The file is read with [`Filtered::try_from_file`](./struct.Filtered.html#method.try_from_file).
+It is only read when it changes, so load it the same way when installing the logger.
+
+Changes are debounced, the file is read once it has been quiet for 200ms. If it can't be read,
+or any rule is malformed, the previous filters are kept and a warning is logged.
+
+The directory containing the file is watched rather than the file itself, so editors that save
+by replacing the file work too.
+
+# Usage
+```rust,no_run
+# use arbitrary_logger::{filtered::{self, Filtered}, logger::Pretty};
+let filters = Filtered::try_from_file("logging.conf")?;
+arbitrary_logger::init_with_filters(Pretty::default(), "trace", filters)?;
+let _watcher = filtered::watch_file("logging.conf")?;
+# Ok::<_, Box<dyn std::error::Error>>(())
+```
+*/
+pub fn watch_file(path: impl AsRef<Path>) -> notify::Result<FilterWatcher> {
+    watch_with(path.as_ref(), |filters| {
+        crate::reload_filters(filters);
+    })
+}
+
+fn watch_with<F>(path: &Path, reload: F) -> notify::Result<FilterWatcher>
+where
+    F: Fn(Filtered) + Send + 'static,
+{
+    let path = std::path::absolute(path)?;
+    let dir = path.parent().unwrap_or(&path).to_path_buf();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let file = path.clone();
+    let mut watcher = notify::recommended_watcher(move |ev: notify::Result<notify::Event>| {
+        if let Ok(ev) = ev {
+            if !ev.kind.is_access() && ev.paths.contains(&file) {
+                let _ = tx.send(());
+            }
+        }
+    })?;
+    watcher.watch(&dir, notify::RecursiveMode::NonRecursive)?;
+
+    std::thread::Builder::new()
+        .name("arbitrary_logger".into())
+        .spawn(move || run(path, rx, reload))
+        .map_err(notify::Error::io)?;
+
+    Ok(FilterWatcher { _watcher: watcher })
+}
+
+// runs until the watcher (and with it, the sender) is dropped
+fn run(path: PathBuf, rx: Receiver<()>, reload: impl Fn(Filtered)) {
+    while rx.recv().is_ok() {
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(..) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        match Filtered::try_from_file(&path) {
+            Ok(filters) => reload(filters),
+            Err(err) => log::warn!(
+                target: "arbitrary_logger",
+                "keeping the previous filters, cannot reload {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_on_change() {
+        let dir = std::env::temp_dir().join(format!("arbitrary_logger-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("logging.conf");
+        std::fs::write(&path, "foo=debug\n").unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _watcher = watch_with(&path, move |filters| {
+            let _ = tx.send(filters.level_for("foo"));
+        })
+        .unwrap();
+
+        // an invalid file is not reloaded
+        std::fs::write(&path, "foo=verbose\n").unwrap();
+        std::thread::sleep(DEBOUNCE * 3);
+        std::fs::write(&path, "foo=warn # quieter\n").unwrap();
+
+        let level = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(level, log::LevelFilter::Error);
+        assert!(rx.try_recv().is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
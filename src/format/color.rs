@@ -1,5 +1,108 @@
 #[cfg(feature = "color")]
-pub use termcolor::Color;
+pub use termcolor::{Color, ColorChoice};
+
+/** A color, with text attributes, for one part of a record
+
+A [`Color`](./enum.Color.html) converts into a style without any attributes.
+
+When deserialized, this is either a color, or a map with a `color` and optionally `bold`,
+`underline` and `dimmed`.
+*/
+#[cfg(feature = "color")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Style {
+    pub color: Color,
+    pub bold: bool,
+    pub underline: bool,
+    pub dimmed: bool,
+}
+
+#[cfg(feature = "color")]
+impl Style {
+    /// A style with just this color
+    pub const fn new(color: Color) -> Self {
+        Self {
+            color,
+            bold: false,
+            underline: false,
+            dimmed: false,
+        }
+    }
+
+    /// Make the text bold
+    pub fn with_bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Underline the text
+    pub fn with_underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Make the text dimmed
+    pub fn with_dimmed(mut self) -> Self {
+        self.dimmed = true;
+        self
+    }
+
+    /// Get the `ColorSpec` to write text in this style
+    pub fn to_spec(&self) -> termcolor::ColorSpec {
+        let mut spec = termcolor::ColorSpec::new();
+        spec.set_fg(Some(self.color))
+            .set_bold(self.bold)
+            .set_underline(self.underline)
+            .set_dimmed(self.dimmed);
+        spec
+    }
+}
+
+#[cfg(feature = "color")]
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        Self::new(color)
+    }
+}
+
+#[cfg(all(feature = "color", feature = "serde"))]
+impl<'de> serde::Deserialize<'de> for Style {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Color(#[serde(deserialize_with = "deserialize_color")] Color),
+            Style {
+                #[serde(deserialize_with = "deserialize_color")]
+                color: Color,
+                #[serde(default)]
+                bold: bool,
+                #[serde(default)]
+                underline: bool,
+                #[serde(default)]
+                dimmed: bool,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Color(color) => Self::new(color),
+            Repr::Style {
+                color,
+                bold,
+                underline,
+                dimmed,
+            } => Self {
+                color,
+                bold,
+                underline,
+                dimmed,
+            },
+        })
+    }
+}
 
 /** Configuration for the level colors
 
@@ -13,68 +116,532 @@ pub use termcolor::Color;
 | Trace | [`Color::Blue`](./enum.Color.html#variant.Blue)     |
 */
 #[cfg(feature = "color")]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
 pub struct LevelColorConfig {
-    pub error: Color,
-    pub warn: Color,
-    pub info: Color,
-    pub debug: Color,
-    pub trace: Color,
+    pub error: Style,
+    pub warn: Style,
+    pub info: Style,
+    pub debug: Style,
+    pub trace: Style,
 }
 
 #[cfg(feature = "color")]
 impl Default for LevelColorConfig {
     fn default() -> Self {
         Self {
-            error: Color::Red,
-            warn: Color::Yellow,
-            info: Color::Green,
-            debug: Color::Cyan,
-            trace: Color::Blue,
+            error: Style::new(Color::Red),
+            warn: Style::new(Color::Yellow),
+            info: Style::new(Color::Green),
+            debug: Style::new(Color::Cyan),
+            trace: Style::new(Color::Blue),
+        }
+    }
+}
+
+#[cfg(feature = "color")]
+impl LevelColorConfig {
+    /// Get the color for this level
+    pub fn color_for(&self, level: log::Level) -> Color {
+        self.style_for(level).color
+    }
+
+    /// Get the style for this level
+    pub fn style_for(&self, level: log::Level) -> Style {
+        match level {
+            log::Level::Error => self.error,
+            log::Level::Warn => self.warn,
+            log::Level::Info => self.info,
+            log::Level::Debug => self.debug,
+            log::Level::Trace => self.trace,
         }
     }
 }
 
 /** Configuration for each part of a record
 
+All of these are plain colors, without any attributes.
+
 ## Default mapping
 | Field        | Color                                                      | RGB       |
 | --           | --                                                         | --        |
 | level        | See [`LevelColorConfig`](./struct.LevelColorConfig.html)   | --        |
 | target       | [`Color::Ansi256(131)`](./enum.Color.html#variant.Ansi256) | `#AF5F5F` |
+| location     | [`Color::Ansi256(241)`](./enum.Color.html#variant.Ansi256) | `#626262` |
 | timestamp    | [`Color::Ansi256(243)`](./enum.Color.html#variant.Ansi256) | `#767676` |
+| sequence     | [`Color::Ansi256(240)`](./enum.Color.html#variant.Ansi256) | `#585858` |
+| thread       | [`Color::Ansi256(245)`](./enum.Color.html#variant.Ansi256) | `#8A8A8A` |
 | continuation | [`Color::Ansi256(237)`](./enum.Color.html#variant.Ansi256) | `#3A3A3A` |
 | message      | [`Color::Ansi256(231)`](./enum.Color.html#variant.Ansi256) | `#FFFFFF` |
+
+With [`target_by_hash`](#method.target_by_hash), each target gets its own color from a palette
+instead.
+
+## Presets
+- [`solarized_dark`](#method.solarized_dark): the [Solarized](https://ethanschoonover.com/solarized/)
+  accents, for its dark background
+- [`monochrome`](#method.monochrome): only shades of gray, telling levels apart with bold,
+  underline and dimmed text instead of hues
+- [`high_contrast`](#method.high_contrast): saturated colors and bold levels
 */
 #[cfg(feature = "color")]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
 pub struct RecordColorConfig {
     pub level: LevelColorConfig,
-    pub target: Color,
-    pub timestamp: Color,
-    pub continuation: Color,
-    pub message: Color,
+    pub target: Style,
+    pub location: Style,
+    pub timestamp: Style,
+    pub sequence: Style,
+    pub thread: Style,
+    pub continuation: Style,
+    pub message: Style,
+    /// When set, the target's color is picked from this palette by hashing the target
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub target_palette: Option<&'static [Color]>,
 }
 
+/// A palette of `Ansi256` colors that read well on dark backgrounds, for
+/// [`RecordColorConfig::target_by_hash`](./struct.RecordColorConfig.html#method.target_by_hash)
+#[cfg(feature = "color")]
+pub const TARGET_PALETTE: &[Color] = &[
+    Color::Ansi256(131),
+    Color::Ansi256(167),
+    Color::Ansi256(173),
+    Color::Ansi256(179),
+    Color::Ansi256(143),
+    Color::Ansi256(107),
+    Color::Ansi256(72),
+    Color::Ansi256(73),
+    Color::Ansi256(67),
+    Color::Ansi256(104),
+    Color::Ansi256(140),
+    Color::Ansi256(175),
+];
+
 #[cfg(feature = "color")]
 impl Default for RecordColorConfig {
     fn default() -> Self {
         Self {
-            target: Color::Ansi256(131),
-            timestamp: Color::Ansi256(243),
-            continuation: Color::Ansi256(237),
-            message: Color::Ansi256(231),
+            target: Style::new(Color::Ansi256(131)),
+            location: Style::new(Color::Ansi256(241)),
+            timestamp: Style::new(Color::Ansi256(243)),
+            sequence: Style::new(Color::Ansi256(240)),
+            thread: Style::new(Color::Ansi256(245)),
+            continuation: Style::new(Color::Ansi256(237)),
+            message: Style::new(Color::Ansi256(231)),
             level: LevelColorConfig::default(),
+            target_palette: None,
         }
     }
 }
 
+#[cfg(feature = "color")]
+impl RecordColorConfig {
+    /// Use this RGB color for the target, for terminals with true color
+    ///
+    /// The attributes of the style are kept
+    pub fn with_target_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.target.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the module path and location, for terminals with true color
+    pub fn with_location_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.location.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the timestamp, for terminals with true color
+    pub fn with_timestamp_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.timestamp.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the sequence number, for terminals with true color
+    pub fn with_sequence_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.sequence.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the thread, for terminals with true color
+    pub fn with_thread_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.thread.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the continuation, for terminals with true color
+    pub fn with_continuation_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.continuation.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use this RGB color for the message, for terminals with true color
+    pub fn with_message_rgb(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.message.color = Color::Rgb(r, g, b);
+        self
+    }
+
+    /// Use the RGB equivalents of the default colors (see the table above)
+    ///
+    /// These match exactly on terminals with true color, which may map the ANSI 256 colors
+    /// slightly differently
+    pub fn true_color() -> Self {
+        Self::default()
+            .with_target_rgb(0xaf, 0x5f, 0x5f)
+            .with_location_rgb(0x62, 0x62, 0x62)
+            .with_timestamp_rgb(0x76, 0x76, 0x76)
+            .with_sequence_rgb(0x58, 0x58, 0x58)
+            .with_thread_rgb(0x8a, 0x8a, 0x8a)
+            .with_continuation_rgb(0x3a, 0x3a, 0x3a)
+            .with_message_rgb(0xff, 0xff, 0xff)
+    }
+
+    /// The [Solarized](https://ethanschoonover.com/solarized/) accents, for its dark background
+    pub fn solarized_dark() -> Self {
+        const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+        const BASE0: Color = Color::Rgb(0x83, 0x94, 0x96);
+        Self {
+            level: LevelColorConfig {
+                error: Style::new(Color::Rgb(0xdc, 0x32, 0x2f)),
+                warn: Style::new(Color::Rgb(0xb5, 0x89, 0x00)),
+                info: Style::new(Color::Rgb(0x85, 0x99, 0x00)),
+                debug: Style::new(Color::Rgb(0x2a, 0xa1, 0x98)),
+                trace: Style::new(Color::Rgb(0x6c, 0x71, 0xc4)),
+            },
+            target: Style::new(Color::Rgb(0xcb, 0x4b, 0x16)),
+            location: Style::new(BASE01),
+            timestamp: Style::new(BASE01),
+            sequence: Style::new(BASE01),
+            thread: Style::new(Color::Rgb(0x26, 0x8b, 0xd2)),
+            continuation: Style::new(BASE01).with_dimmed(),
+            message: Style::new(BASE0),
+            target_palette: None,
+        }
+    }
+
+    /// Only shades of gray, with levels told apart by bold, underline and dimmed text
+    ///
+    /// Nothing relies on telling hues apart, which helps with color blindness
+    pub fn monochrome() -> Self {
+        const BRIGHT: Color = Color::Ansi256(255);
+        const GRAY: Color = Color::Ansi256(248);
+        const DARK: Color = Color::Ansi256(242);
+        Self {
+            level: LevelColorConfig {
+                error: Style::new(BRIGHT).with_bold().with_underline(),
+                warn: Style::new(BRIGHT).with_bold(),
+                info: Style::new(BRIGHT),
+                debug: Style::new(GRAY),
+                trace: Style::new(DARK).with_dimmed(),
+            },
+            target: Style::new(GRAY).with_underline(),
+            location: Style::new(DARK),
+            timestamp: Style::new(DARK),
+            sequence: Style::new(DARK),
+            thread: Style::new(GRAY),
+            continuation: Style::new(DARK).with_dimmed(),
+            message: Style::new(BRIGHT),
+            target_palette: None,
+        }
+    }
+
+    /// Saturated colors, with bold levels
+    pub fn high_contrast() -> Self {
+        Self {
+            level: LevelColorConfig {
+                error: Style::new(Color::Ansi256(196)).with_bold(),
+                warn: Style::new(Color::Ansi256(226)).with_bold(),
+                info: Style::new(Color::Ansi256(46)).with_bold(),
+                debug: Style::new(Color::Ansi256(51)).with_bold(),
+                trace: Style::new(Color::Ansi256(201)).with_bold(),
+            },
+            target: Style::new(Color::Ansi256(214)),
+            location: Style::new(Color::Ansi256(252)),
+            timestamp: Style::new(Color::Ansi256(252)),
+            sequence: Style::new(Color::Ansi256(252)),
+            thread: Style::new(Color::Ansi256(159)),
+            continuation: Style::new(Color::Ansi256(250)),
+            message: Style::new(Color::Ansi256(231)),
+            target_palette: None,
+        }
+    }
+
+    /// Color each target with a color from `palette`, picked by hashing the target
+    ///
+    /// The same target always gets the same color, across runs. The attributes of the target's
+    /// style are kept. An empty palette uses the target's color. See
+    /// [`TARGET_PALETTE`](./constant.TARGET_PALETTE.html) for a default palette.
+    pub fn target_by_hash(mut self, palette: &'static [Color]) -> Self {
+        self.target_palette.replace(palette);
+        self
+    }
+
+    /// Get the style for this target
+    pub fn target_style(&self, target: &str) -> Style {
+        let mut style = self.target;
+        if let Some(palette) = self.target_palette.filter(|p| !p.is_empty()) {
+            style.color = palette[palette_index(target, palette.len())];
+        }
+        style
+    }
+}
+
+/// FNV-1a, so the index doesn't change between runs (unlike with a `RandomState`)
+#[cfg(feature = "color")]
+fn palette_index(target: &str, len: usize) -> usize {
+    let hash = target
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    (hash % len as u64) as usize
+}
+
 #[cfg(not(feature = "color"))]
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 /// Empty color config when the _feature_ `color` is disabled
 pub struct RecordColorConfig {}
 
 #[cfg(not(feature = "color"))]
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 /// Empty color config when the _feature_ `color` is disabled
 pub struct LevelColorConfig {}
+
+/// Parses a color name (`red`), an ANSI 256 index (`131`), an `r,g,b` triple or a `#rrggbb` hex string
+#[cfg(all(feature = "color", feature = "serde"))]
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+    parse_color(&s).ok_or_else(|| {
+        serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(&s),
+            &"a color name or hex string",
+        )
+    })
+}
+
+#[cfg(all(feature = "color", feature = "serde"))]
+fn parse_color(s: &str) -> Option<Color> {
+    match s.starts_with('#') {
+        true => parse_hex(s),
+        false => s.parse().ok(),
+    }
+}
+
+/// Parse a `#rrggbb` (or `rrggbb`) hex string into a [`Color::Rgb`](./enum.Color.html#variant.Rgb)
+///
+/// Returns `None` for anything else
+#[cfg(feature = "color")]
+pub fn parse_hex(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::Rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8))
+}
+
+#[cfg(all(test, feature = "color"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse_hex("#af5f5f"), Some(Color::Rgb(0xaf, 0x5f, 0x5f)));
+        assert_eq!(parse_hex("#AF5F5F"), Some(Color::Rgb(0xaf, 0x5f, 0x5f)));
+        assert_eq!(parse_hex("000000"), Some(Color::Rgb(0, 0, 0)));
+        assert_eq!(parse_hex("#ffffff"), Some(Color::Rgb(0xff, 0xff, 0xff)));
+
+        for bad in &[
+            "", "#", "#af5f5", "#af5f5f0", "#gggggg", "#+fffff", "##af5f5", "red",
+        ] {
+            assert_eq!(parse_hex(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn rgb() {
+        let mut config = RecordColorConfig::default();
+        config.target = config.target.with_bold();
+        let config = config.with_target_rgb(1, 2, 3);
+        assert_eq!(config.target, Style::new(Color::Rgb(1, 2, 3)).with_bold());
+        assert_eq!(config.message, RecordColorConfig::default().message);
+
+        let config = RecordColorConfig::true_color();
+        assert_eq!(Some(config.target.color), parse_hex("#AF5F5F"));
+        assert_eq!(Some(config.location.color), parse_hex("#626262"));
+        assert_eq!(Some(config.timestamp.color), parse_hex("#767676"));
+        assert_eq!(Some(config.sequence.color), parse_hex("#585858"));
+        assert_eq!(Some(config.thread.color), parse_hex("#8A8A8A"));
+        assert_eq!(Some(config.continuation.color), parse_hex("#3A3A3A"));
+        assert_eq!(Some(config.message.color), parse_hex("#FFFFFF"));
+    }
+
+    #[test]
+    fn presets() {
+        let default = RecordColorConfig::default();
+        let presets = [
+            RecordColorConfig::solarized_dark(),
+            RecordColorConfig::monochrome(),
+            RecordColorConfig::high_contrast(),
+        ];
+        for (i, preset) in presets.iter().enumerate() {
+            assert_ne!(*preset, default, "{}", i);
+            assert_ne!(preset.level, default.level, "{}", i);
+            assert_ne!(preset.target, default.target, "{}", i);
+            assert_eq!(preset.target_palette, None, "{}", i);
+            for other in &presets[i + 1..] {
+                assert_ne!(preset, other);
+            }
+        }
+
+        let gray = |style: &Style| match style.color {
+            Color::Ansi256(n) => n >= 232,
+            _ => false,
+        };
+        let mono = RecordColorConfig::monochrome();
+        let levels = [
+            log::Level::Error,
+            log::Level::Warn,
+            log::Level::Info,
+            log::Level::Debug,
+            log::Level::Trace,
+        ];
+        for style in levels
+            .iter()
+            .map(|&level| mono.level.style_for(level))
+            .chain(vec![
+                mono.target,
+                mono.location,
+                mono.timestamp,
+                mono.sequence,
+                mono.thread,
+                mono.continuation,
+                mono.message,
+            ])
+        {
+            assert!(gray(&style), "{:?}", style);
+        }
+        for (i, a) in levels.iter().enumerate() {
+            for b in &levels[i + 1..] {
+                assert_ne!(mono.level.style_for(*a), mono.level.style_for(*b));
+            }
+        }
+
+        assert!(levels
+            .iter()
+            .all(|&level| RecordColorConfig::high_contrast()
+                .level
+                .style_for(level)
+                .bold));
+    }
+
+    #[test]
+    fn target_by_hash() {
+        let a = palette_index("my_crate::net", TARGET_PALETTE.len());
+        assert_eq!(a, palette_index("my_crate::net", TARGET_PALETTE.len()));
+        assert!(a < TARGET_PALETTE.len());
+        // fixed, so it's the same across runs
+        assert_eq!(
+            palette_index("", 7),
+            (0xcbf2_9ce4_8422_2325_u64 % 7) as usize
+        );
+
+        let config = RecordColorConfig::default();
+        assert_eq!(config.target_style("my_crate::net"), config.target);
+
+        let mut config = RecordColorConfig::default().target_by_hash(TARGET_PALETTE);
+        config.target = config.target.with_underline();
+        let style = config.target_style("my_crate::net");
+        assert_eq!(style, config.target_style("my_crate::net"));
+        assert_eq!(style, Style::new(TARGET_PALETTE[a]).with_underline());
+
+        assert!(["b", "c", "d", "e", "f", "g", "h"]
+            .iter()
+            .any(|target| config.target_style(target) != config.target_style("a")));
+
+        let config = RecordColorConfig::default().target_by_hash(&[]);
+        assert_eq!(config.target_style("a"), config.target);
+    }
+
+    #[test]
+    fn style_spec() {
+        let spec = Style::new(Color::Red).to_spec();
+        assert_eq!(spec.fg(), Some(&Color::Red));
+        assert!(!spec.bold() && !spec.underline() && !spec.dimmed());
+        assert_eq!(&spec, termcolor::ColorSpec::new().set_fg(Some(Color::Red)));
+
+        let spec = Style::new(Color::Blue).with_bold().to_spec();
+        assert!(spec.bold() && !spec.underline() && !spec.dimmed());
+
+        let spec = Style::new(Color::Blue).with_underline().to_spec();
+        assert!(!spec.bold() && spec.underline() && !spec.dimmed());
+
+        let spec = Style::new(Color::Blue).with_dimmed().with_bold().to_spec();
+        assert!(spec.bold() && !spec.underline() && spec.dimmed());
+        assert_eq!(spec.fg(), Some(&Color::Blue));
+        assert_eq!(spec.bg(), None);
+    }
+
+    #[test]
+    fn default_styles_are_plain_colors() {
+        let config = RecordColorConfig::default();
+        for style in &[
+            config.target,
+            config.location,
+            config.timestamp,
+            config.sequence,
+            config.thread,
+            config.continuation,
+            config.message,
+            config.level.style_for(log::Level::Error),
+            config.level.style_for(log::Level::Trace),
+        ] {
+            assert_eq!(*style, Style::new(style.color));
+        }
+        assert_eq!(config.level.color_for(log::Level::Warn), Color::Yellow);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize() {
+        use serde::de::{value, Deserialize};
+
+        let de = value::MapDeserializer::<_, value::Error>::new(
+            vec![("error", "magenta"), ("warn", "#ff8000"), ("trace", "243")].into_iter(),
+        );
+        let config = LevelColorConfig::deserialize(de).unwrap();
+        assert_eq!(config.error, Style::new(Color::Magenta));
+        assert_eq!(config.warn, Style::new(Color::Rgb(0xff, 0x80, 0x00)));
+        assert_eq!(config.info, LevelColorConfig::default().info);
+        assert_eq!(config.trace, Style::new(Color::Ansi256(243)));
+
+        let de =
+            value::MapDeserializer::<_, value::Error>::new(vec![("target", "#AF5F5F")].into_iter());
+        let config = RecordColorConfig::deserialize(de).unwrap();
+        assert_eq!(config.target, Style::new(Color::Rgb(0xaf, 0x5f, 0x5f)));
+        assert_eq!(config.message, RecordColorConfig::default().message);
+
+        let config: RecordColorConfig = serde_json::from_str(
+            r#"{"level": {"error": {"color": "red", "bold": true}}, "continuation": {"color": "240", "dimmed": true}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.level.error, Style::new(Color::Red).with_bold());
+        assert_eq!(
+            config.continuation,
+            Style::new(Color::Ansi256(240)).with_dimmed()
+        );
+        assert!(serde_json::from_str::<Style>(r#"{"bold": true}"#).is_err());
+
+        for bad in &["#af5f5", "#gggggg", "not a color"] {
+            let de =
+                value::MapDeserializer::<_, value::Error>::new(vec![("target", *bad)].into_iter());
+            assert!(RecordColorConfig::deserialize(de).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use core::fmt::Write;
+
+use super::level::LevelLabels;
+
+/** A record writer for a `core::fmt::Write`, without `std`
+
+This writes the level, target and message like [`Writer`](./struct.Writer.html) does (without
+colors, key-values or wrapping), so the same layout can be written to e.g. a UART or a fixed
+buffer on an embedded target. It's the only part of the crate available without the `std`
+feature.
+
+# Usage
+```rust
+# use arbitrary_logger::format::new_fmt_writer;
+let record = log::Record::builder()
+    .args(format_args!("hello"))
+    .level(log::Level::Info)
+    .target("app")
+    .build();
+
+let mut line = String::new();
+new_fmt_writer(&record).line(&mut line).unwrap();
+assert_eq!(line, "INFO  [app] hello\n");
+```
+*/
+#[derive(Copy, Clone)]
+pub struct FmtWriter<'a, 'b: 'a> {
+    record: &'a log::Record<'b>,
+    separator: &'a str,
+    target_brackets: (&'a str, &'a str),
+    level_labels: LevelLabels,
+}
+
+/// Wrap a record with a `core::fmt::Write` writer
+pub fn new_fmt_writer<'a, 'b: 'a>(record: &'a log::Record<'b>) -> FmtWriter<'a, 'b> {
+    FmtWriter {
+        record,
+        separator: " ",
+        target_brackets: ("[", "]"),
+        level_labels: LevelLabels::default(),
+    }
+}
+
+impl<'a, 'b: 'a> FmtWriter<'a, 'b> {
+    /// Use this separator between the fields, instead of a single space
+    pub fn with_separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Surround the target with these, instead of `[` and `]`
+    pub fn with_target_brackets(mut self, open: &'a str, close: &'a str) -> Self {
+        self.target_brackets = (open, close);
+        self
+    }
+
+    /// Use these labels for the level
+    pub fn with_level_labels(mut self, level_labels: LevelLabels) -> Self {
+        self.level_labels = level_labels;
+        self
+    }
+
+    /// Write the level, padded to the widest label
+    pub fn level(&self, w: &mut dyn Write) -> core::fmt::Result {
+        write!(
+            w,
+            "{:<width$}",
+            self.level_labels.label_for(self.record.level()),
+            width = self.level_labels.width()
+        )
+    }
+
+    /// Write the target, in its brackets
+    pub fn target(&self, w: &mut dyn Write) -> core::fmt::Result {
+        let (open, close) = self.target_brackets;
+        write!(w, "{}{}{}", open, self.record.target(), close)
+    }
+
+    /// Write the separator
+    pub fn separator(&self, w: &mut dyn Write) -> core::fmt::Result {
+        w.write_str(self.separator)
+    }
+
+    /// Write the message
+    pub fn message(&self, w: &mut dyn Write) -> core::fmt::Result {
+        w.write_fmt(*self.record.args())
+    }
+
+    /// End the record with a new line
+    pub fn end(&self, w: &mut dyn Write) -> core::fmt::Result {
+        w.write_char('\n')
+    }
+
+    /// Write the whole line: the level, the target and the message
+    pub fn line(&self, w: &mut dyn Write) -> core::fmt::Result {
+        self.level(w)?;
+        self.separator(w)?;
+        self.target(w)?;
+        self.separator(w)?;
+        self.message(w)?;
+        self.end(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // no allocation, like on an embedded target
+    struct Fixed {
+        data: [u8; 64],
+        len: usize,
+    }
+
+    impl Write for Fixed {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn line() {
+        let record = log::Record::builder()
+            .args(format_args!("hello 42"))
+            .level(log::Level::Warn)
+            .target("app")
+            .build();
+
+        let mut fixed = Fixed {
+            data: [0; 64],
+            len: 0,
+        };
+        new_fmt_writer(&record)
+            .with_separator(" | ")
+            .with_target_brackets("<", ">")
+            .with_level_labels(LevelLabels::short())
+            .line(&mut fixed)
+            .unwrap();
+        assert_eq!(&fixed.data[..fixed.len], b"W | <app> | hello 42\n");
+
+        let mut small = Fixed {
+            data: [0; 64],
+            len: 60,
+        };
+        assert!(new_fmt_writer(&record).line(&mut small).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+/** Labels used when writing the level of a record
+
+The level is padded to the width of the widest label in the set
+
+## Default mapping
+| Level | Label   | Short |
+| --    | --      | --    |
+| Error | `ERROR` | `E`   |
+| Warn  | `WARN`  | `W`   |
+| Info  | `INFO`  | `I`   |
+| Debug | `DEBUG` | `D`   |
+| Trace | `TRACE` | `T`   |
+
+`gutter` has symbols instead, for a gutter at the start of the line:
+`‼` for `Error`, `▲` for `Warn`, `▶` for `Info`, `·` for `Debug` and `…` for `Trace`
+*/
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LevelLabels {
+    pub error: &'static str,
+    pub warn: &'static str,
+    pub info: &'static str,
+    pub debug: &'static str,
+    pub trace: &'static str,
+}
+
+impl LevelLabels {
+    /// Single letter labels, e.g. `E` for `Error`
+    pub fn short() -> Self {
+        Self {
+            error: "E",
+            warn: "W",
+            info: "I",
+            debug: "D",
+            trace: "T",
+        }
+    }
+
+    /// Single symbols for a gutter, e.g. `‼` for `Error`
+    pub fn gutter() -> Self {
+        Self {
+            error: "‼",
+            warn: "▲",
+            info: "▶",
+            debug: "·",
+            trace: "…",
+        }
+    }
+
+    /// Get the label for this level
+    pub fn label_for(&self, level: log::Level) -> &'static str {
+        match level {
+            log::Level::Error => self.error,
+            log::Level::Warn => self.warn,
+            log::Level::Info => self.info,
+            log::Level::Debug => self.debug,
+            log::Level::Trace => self.trace,
+        }
+    }
+
+    /// Width of the widest label, in characters
+    pub fn width(&self) -> usize {
+        [self.error, self.warn, self.info, self.debug, self.trace]
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LevelLabels {
+    fn default() -> Self {
+        Self {
+            error: "ERROR",
+            warn: "WARN",
+            info: "INFO",
+            debug: "DEBUG",
+            trace: "TRACE",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width() {
+        assert_eq!(LevelLabels::default().width(), 5);
+        assert_eq!(LevelLabels::short().width(), 1);
+        assert_eq!(LevelLabels::gutter().width(), 1);
+
+        let custom = LevelLabels {
+            warn: "WARNING",
+            ..LevelLabels::default()
+        };
+        assert_eq!(custom.width(), 7);
+
+        let custom = LevelLabels {
+            error: "✗",
+            warn: "⚠",
+            info: "i",
+            debug: "·",
+            trace: "…",
+        };
+        assert_eq!(custom.width(), 1);
+    }
+}
@@ -2,17 +2,82 @@
 
 */
 
+#[cfg(feature = "std")]
 mod writer;
+#[cfg(feature = "std")]
 pub use writer::*;
 
+mod fmt;
+pub use fmt::*;
+
+mod level;
+pub use level::*;
+
+#[cfg(feature = "std")]
 mod time;
+#[cfg(feature = "std")]
 pub use time::*;
 
 mod color;
 pub use color::*;
 
-/// Primary trait for printing a log record
+/** Primary trait for printing a log record
+
+`print` takes `&self` and is called from whichever thread logged the record, possibly from
+several threads at once. An implementation can hold its output in the struct instead of
+opening it per record: put it behind a `Mutex` (or another form of interior mutability), and
+hold the lock while writing a whole record so lines don't interleave.
+
+Implement `flush` if the output is buffered, it's called by `log::logger().flush()`.
+
+# Example
+A logger writing to a file, holding a `BufWriter`
+```rust
+use arbitrary_logger::format::Print;
+use std::{fs::File, io::{BufWriter, Write}, sync::Mutex};
+
+struct FileLogger(Mutex<BufWriter<File>>);
+
+impl FileLogger {
+    fn create(path: &str) -> std::io::Result<Self> {
+        File::create(path).map(|file| Self(Mutex::new(BufWriter::new(file))))
+    }
+}
+
+impl Print for FileLogger {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut file = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        writeln!(file, "{} [{}] {}", record.level(), record.target(), record.args())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).flush()
+    }
+}
+
+# let path = std::env::temp_dir().join("arbitrary_logger-print-example.log");
+# let path = path.to_str().unwrap();
+let logger = FileLogger::create(path)?;
+arbitrary_logger::try_init(logger, log::LevelFilter::Trace);
+# Ok::<_, std::io::Error>(())
+```
+*/
+#[cfg(feature = "std")]
 pub trait Print: Send + Sync {
     /// Print this log record
     fn print(&self, record: &log::Record) -> std::io::Result<()>;
+
+    /// Flush any buffered records
+    ///
+    /// This is called by `log::Log::flush`, the default does nothing
+    fn flush(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A trait for rendering a log record to a writer, rather than to stdout
+#[cfg(feature = "std")]
+pub trait Render: Send + Sync {
+    /// Render this log record, including the trailing newline
+    fn render(&self, record: &log::Record, w: &mut dyn std::io::Write) -> std::io::Result<()>;
 }
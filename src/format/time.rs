@@ -6,6 +6,18 @@
 pub trait FormatTime: Send + Sync {
     /// Format this time to the provided writer
     fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()>;
+
+    /// Format a specific time, e.g. one carried by a replayed record, instead of now
+    ///
+    /// The default ignores `time` and calls `format_time`
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let _ = time;
+        self.format_time(w)
+    }
 }
 
 /// Implementation for when no time should be formatted
@@ -16,7 +28,7 @@ impl FormatTime for () {
 }
 
 /// Implementation for functions that takes a Writer
-impl<'a, F> FormatTime for F
+impl<F> FormatTime for F
 where
     F: Fn(&mut dyn std::io::Write) -> std::io::Result<()>,
     F: Send + Sync,
@@ -26,39 +38,610 @@ where
     }
 }
 
+/// Implementation for a shared formatter, e.g. an `Uptime` used by several loggers
+impl<T: FormatTime + ?Sized> FormatTime for std::sync::Arc<T> {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        (**self).format_time(w)
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        (**self).format_time_at(w, time)
+    }
+}
+
 /// A Timestamp formatter
 ///
 /// This is a UNIX-timestamp
 #[derive(Default)]
 pub struct Timestamp {
     style: TimestampStyle,
+    min_width: usize,
 }
 
 impl Timestamp {
     /// Create a new timestamp with the provided style
     pub fn new(style: TimestampStyle) -> Self {
-        Self { style }
+        Self {
+            style,
+            min_width: 0,
+        }
+    }
+
+    /// Zero-pad the whole seconds to at least `width` digits, keeping the column aligned
+    pub fn with_min_width(mut self, width: usize) -> Self {
+        self.min_width = width;
+        self
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        let (secs, width) = (elapsed.as_secs(), self.min_width);
+        let nanos = elapsed.subsec_nanos();
+        match self.style.digits() {
+            0 => write!(w, "{:0width$}", secs, width = width),
+            digits => write!(
+                w,
+                "{:0width$}.{}",
+                secs,
+                scale(nanos, digits),
+                width = width
+            ),
+        }
     }
 }
 
 impl FormatTime for Timestamp {
     fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
-        let elapsed = std::time::SystemTime::now()
+        self.format_time_at(w, std::time::SystemTime::now())
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let elapsed = time
             .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+            .map_err(std::io::Error::other)?;
+        self.format_at(w, elapsed)
+    }
+}
 
-        let nanos = elapsed.subsec_nanos();
-        match self.style {
-            TimestampStyle::Whole => write!(w, "{}", elapsed.as_secs()),
-            TimestampStyle::Fractional(width) if width == 0 => write!(w, "{}", elapsed.as_secs()),
-            TimestampStyle::Fractional(width) => {
-                write!(w, "{}.{}", elapsed.as_secs(), scale(nanos, width),)
+/// A UTC wall-clock formatter
+///
+/// This prints the current date and time, e.g. `2024-06-01T12:34:56Z`, or with
+/// `TimestampStyle::Fractional(3)` something like `2024-06-01T12:34:56.789Z`
+///
+/// The date and time of the last second is kept, so records in the same second only format
+/// the fraction
+#[derive(Default)]
+pub struct Utc {
+    date: DateStyle,
+    style: TimestampStyle,
+    cache: std::sync::Mutex<(Option<u64>, Vec<u8>)>,
+}
+
+impl Utc {
+    /// Create a new UTC formatter with the provided date style
+    pub fn new(date: DateStyle) -> Self {
+        Self {
+            date,
+            style: TimestampStyle::Whole,
+            cache: Default::default(),
+        }
+    }
+
+    /// Print the seconds with this style, up to 9 fractional digits
+    pub fn with_style(mut self, style: TimestampStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    #[cfg(test)]
+    fn format_secs(&self, w: &mut dyn std::io::Write, secs: u64) -> std::io::Result<()> {
+        self.format_at(w, std::time::Duration::from_secs(secs))
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        self.format_date_time(w, elapsed.as_secs())?;
+        self.format_fraction(w, elapsed.subsec_nanos())?;
+        write!(w, "Z")
+    }
+
+    fn format_fraction(&self, w: &mut dyn std::io::Write, nanos: u32) -> std::io::Result<()> {
+        match self.style.digits() {
+            0 => Ok(()),
+            digits => write!(w, ".{}", scale(nanos, digits)),
+        }
+    }
+
+    // RFC 3339 with milliseconds, e.g. `2024-06-01T12:34:56.789Z`
+    pub(crate) fn format_millis(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        self.format_date_time(w, elapsed.as_secs())?;
+        write!(w, ".{:03}Z", elapsed.subsec_millis())
+    }
+
+    // the Common Log Format's date, e.g. `01/Jun/2024:12:34:56 +0000`
+    #[cfg_attr(not(feature = "kv"), allow(dead_code))]
+    pub(crate) fn format_common_log(w: &mut dyn std::io::Write, secs: u64) -> std::io::Result<()> {
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let rem = secs % 86_400;
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        write!(
+            w,
+            "{:02}/{}/{:04}:{:02}:{:02}:{:02} +0000",
+            day,
+            MONTHS[month as usize - 1],
+            year,
+            rem / 3600,
+            rem / 60 % 60,
+            rem % 60
+        )
+    }
+
+    fn format_date_time(&self, w: &mut dyn std::io::Write, secs: u64) -> std::io::Result<()> {
+        // another thread is using the cache, so don't wait for it
+        let mut cache = match self.cache.try_lock() {
+            Ok(cache) => cache,
+            Err(std::sync::TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return self.write_date_time(w, secs),
+        };
+
+        let (cached, prefix) = &mut *cache;
+        if *cached != Some(secs) {
+            prefix.clear();
+            self.write_date_time(prefix, secs)?;
+            *cached = Some(secs);
+        }
+        w.write_all(prefix)
+    }
+
+    fn write_date_time(&self, w: &mut dyn std::io::Write, secs: u64) -> std::io::Result<()> {
+        let days = (secs / 86_400) as i64;
+        let rem = secs % 86_400;
+        let (year, month, day) = civil_from_days(days);
+
+        match self.date {
+            DateStyle::Calendar => write!(w, "{:04}-{:02}-{:02}", year, month, day)?,
+            DateStyle::Ordinal => write!(w, "{:04}-{:03}", year, ordinal(days, year))?,
+            DateStyle::Week => {
+                let (year, week, weekday) = iso_week(days, year);
+                write!(w, "{:04}-W{:02}-{}", year, week, weekday)?
+            }
+        }
+
+        write!(w, "T{:02}:{:02}:{:02}", rem / 3600, rem / 60 % 60, rem % 60)
+    }
+}
+
+impl FormatTime for Utc {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.format_time_at(w, std::time::SystemTime::now())
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let elapsed = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(std::io::Error::other)?;
+        self.format_at(w, elapsed)
+    }
+}
+
+/// An RFC 3339 formatter, in UTC
+///
+/// This prints e.g. `2024-06-01T12:34:56Z`, or with `TimestampStyle::Fractional(3)`
+/// `2024-06-01T12:34:56.789Z`. This is a [`Utc`](./struct.Utc.html) with the calendar date
+#[derive(Default)]
+pub struct Rfc3339 {
+    utc: Utc,
+}
+
+impl Rfc3339 {
+    /// Create a new RFC 3339 formatter with the provided style, up to 9 fractional digits
+    pub fn new(style: TimestampStyle) -> Self {
+        Self {
+            utc: Utc::new(DateStyle::Calendar).with_style(style),
+        }
+    }
+}
+
+impl FormatTime for Rfc3339 {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.utc.format_time(w)
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        self.utc.format_time_at(w, time)
+    }
+}
+
+/** A UTC formatter with a `strftime`-style format
+
+The format is checked when this is created. The supported specifiers are:
+
+| Specifier | Example      | Meaning                                            |
+| --        | --           | --                                                 |
+| `%Y`      | `2024`       | the year                                           |
+| `%y`      | `24`         | the year, last two digits                          |
+| `%m`      | `06`         | the month                                          |
+| `%b`      | `Jun`        | the abbreviated month name                         |
+| `%d`      | `01`         | the day of the month                               |
+| `%e`      | ` 1`         | the day of the month, padded with a space          |
+| `%j`      | `153`        | the day of the year                                |
+| `%H`      | `12`         | the hour                                           |
+| `%M`      | `34`         | the minute                                         |
+| `%S`      | `56`         | the second                                         |
+| `%f`      | `789000000`  | the nanoseconds, `%3f` for 3 digits (up to 9)      |
+| `%.3f`    | `.789`       | like `%3f`, with a leading `.`                     |
+| `%s`      | `1717245296` | the seconds since the UNIX epoch                   |
+| `%F`      | `2024-06-01` | same as `%Y-%m-%d`                                 |
+| `%T`      | `12:34:56`   | same as `%H:%M:%S`                                 |
+| `%z`      | `+0000`      | the offset, always UTC                             |
+| `%Z`      | `UTC`        | the time zone, always UTC                          |
+| `%%`      | `%`          | a literal `%`                                      |
+
+```rust
+# use arbitrary_logger::format::StrftimeTime;
+let time = StrftimeTime::new("%H:%M:%S%.3f").unwrap();
+assert!(StrftimeTime::new("%H:%Q").is_err());
+```
+*/
+pub struct StrftimeTime {
+    items: Vec<TimeItem>,
+}
+
+enum TimeItem {
+    Literal(String),
+    Year,
+    ShortYear,
+    Month,
+    MonthName,
+    Day,
+    SpaceDay,
+    Ordinal,
+    Hour,
+    Minute,
+    Second,
+    Fraction { digits: usize, dot: bool },
+    Epoch,
+    Offset,
+    Zone,
+}
+
+impl StrftimeTime {
+    /// Create a new formatter, returning an error for an unknown or incomplete specifier
+    pub fn new(format: impl Into<String>) -> Result<Self, TimeFormatError> {
+        let format = format.into();
+        let mut items = vec![];
+        let mut literal = String::new();
+        let mut chars = format.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                literal.push(ch);
+                continue;
+            }
+
+            let spec = chars.next().ok_or_else(|| TimeFormatError("%".into()))?;
+            let item = match spec {
+                '%' => {
+                    literal.push('%');
+                    continue;
+                }
+                'Y' => vec![TimeItem::Year],
+                'y' => vec![TimeItem::ShortYear],
+                'm' => vec![TimeItem::Month],
+                'b' => vec![TimeItem::MonthName],
+                'd' => vec![TimeItem::Day],
+                'e' => vec![TimeItem::SpaceDay],
+                'j' => vec![TimeItem::Ordinal],
+                'H' => vec![TimeItem::Hour],
+                'M' => vec![TimeItem::Minute],
+                'S' => vec![TimeItem::Second],
+                's' => vec![TimeItem::Epoch],
+                'z' => vec![TimeItem::Offset],
+                'Z' => vec![TimeItem::Zone],
+                'F' => vec![
+                    TimeItem::Year,
+                    TimeItem::Literal("-".into()),
+                    TimeItem::Month,
+                    TimeItem::Literal("-".into()),
+                    TimeItem::Day,
+                ],
+                'T' => vec![
+                    TimeItem::Hour,
+                    TimeItem::Literal(":".into()),
+                    TimeItem::Minute,
+                    TimeItem::Literal(":".into()),
+                    TimeItem::Second,
+                ],
+                'f' => vec![TimeItem::Fraction {
+                    digits: 9,
+                    dot: false,
+                }],
+                '.' | '1'..='9' => {
+                    let dot = spec == '.';
+                    let digits = if dot { chars.next() } else { Some(spec) };
+                    let f = chars.next();
+                    match (digits.and_then(|d| d.to_digit(10)), f) {
+                        (Some(digits @ 1..=9), Some('f')) => vec![TimeItem::Fraction {
+                            digits: digits as usize,
+                            dot,
+                        }],
+                        _ => {
+                            let mut spec = format!("%{}", spec);
+                            spec.extend(digits.filter(|_| dot).into_iter().chain(f));
+                            return Err(TimeFormatError(spec));
+                        }
+                    }
+                }
+                spec => return Err(TimeFormatError(format!("%{}", spec))),
+            };
+
+            if !literal.is_empty() {
+                items.push(TimeItem::Literal(std::mem::take(&mut literal)));
             }
+            items.extend(item);
         }
+
+        if !literal.is_empty() {
+            items.push(TimeItem::Literal(literal));
+        }
+        Ok(Self { items })
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let secs = elapsed.as_secs();
+        let days = (secs / 86_400) as i64;
+        let rem = secs % 86_400;
+        let (year, month, day) = civil_from_days(days);
+
+        for item in &self.items {
+            match item {
+                TimeItem::Literal(literal) => w.write_all(literal.as_bytes())?,
+                TimeItem::Year => write!(w, "{:04}", year)?,
+                TimeItem::ShortYear => write!(w, "{:02}", year.rem_euclid(100))?,
+                TimeItem::Month => write!(w, "{:02}", month)?,
+                TimeItem::MonthName => w.write_all(MONTHS[month as usize - 1].as_bytes())?,
+                TimeItem::Day => write!(w, "{:02}", day)?,
+                TimeItem::SpaceDay => write!(w, "{:2}", day)?,
+                TimeItem::Ordinal => write!(w, "{:03}", ordinal(days, year))?,
+                TimeItem::Hour => write!(w, "{:02}", rem / 3600)?,
+                TimeItem::Minute => write!(w, "{:02}", rem / 60 % 60)?,
+                TimeItem::Second => write!(w, "{:02}", rem % 60)?,
+                TimeItem::Fraction { digits, dot } => {
+                    if *dot {
+                        w.write_all(b".")?
+                    }
+                    write!(w, "{}", scale(elapsed.subsec_nanos(), *digits))?
+                }
+                TimeItem::Epoch => write!(w, "{}", secs)?,
+                TimeItem::Offset => w.write_all(b"+0000")?,
+                TimeItem::Zone => w.write_all(b"UTC")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FormatTime for StrftimeTime {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.format_time_at(w, std::time::SystemTime::now())
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let elapsed = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(std::io::Error::other)?;
+        self.format_at(w, elapsed)
     }
 }
 
-// TODO UTC timestamp
+/// An error returned when a time format has an unknown or incomplete specifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeFormatError(String);
+
+impl std::fmt::Display for TimeFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown time format specifier: `{}`", self.0)
+    }
+}
+
+impl std::error::Error for TimeFormatError {}
+
+/** A local wall-clock formatter
+
+This prints the current date and time with the local offset, e.g.
+`2024-06-01T14:34:56+02:00`, or with `TimestampStyle::Fractional(3)` something like
+`2024-06-01T14:34:56.789+02:00`.
+
+The offset is looked up once, when this is created, so a daylight saving change isn't picked
+up until it's created again. On some platforms (e.g. Linux) the lookup isn't possible once the
+program has more than one thread, so create this early in `main`. If the offset can't be
+found, this prints UTC instead, with a `Z` in place of the offset.
+
+This requires the `time` feature
+*/
+#[cfg(feature = "time")]
+pub struct LocalTime {
+    utc: Utc,
+    // `None` when the local offset couldn't be found
+    offset: Option<::time::UtcOffset>,
+}
+
+#[cfg(feature = "time")]
+impl LocalTime {
+    /// Create a new local formatter with the provided style, looking up the local offset
+    pub fn new(style: TimestampStyle) -> Self {
+        Self {
+            utc: Utc::default().with_style(style),
+            offset: ::time::UtcOffset::current_local_offset().ok(),
+        }
+    }
+
+    /// Whether the local offset was found, otherwise this prints UTC
+    pub fn is_local(&self) -> bool {
+        self.offset.is_some()
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        let offset = match self.offset {
+            Some(offset) => offset,
+            None => return self.utc.format_at(w, elapsed),
+        };
+
+        let secs = elapsed.as_secs() as i64 + offset.whole_seconds() as i64;
+        self.utc.format_date_time(w, secs.max(0) as u64)?;
+        self.utc.format_fraction(w, elapsed.subsec_nanos())?;
+
+        let sign = if offset.is_negative() { '-' } else { '+' };
+        let (hours, minutes, _) = offset.as_hms();
+        write!(w, "{}{:02}:{:02}", sign, hours.abs(), minutes.abs())
+    }
+}
+
+/// Defaults to whole seconds
+#[cfg(feature = "time")]
+impl Default for LocalTime {
+    fn default() -> Self {
+        Self::new(TimestampStyle::Whole)
+    }
+}
+
+#[cfg(feature = "time")]
+impl FormatTime for LocalTime {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.format_time_at(w, std::time::SystemTime::now())
+    }
+
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let elapsed = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(std::io::Error::other)?;
+        self.format_at(w, elapsed)
+    }
+}
+
+/// Date style to determine how the date portion of a wall-clock time should be printed
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DateStyle {
+    /// ISO-8601 calendar date, e.g. `2024-01-03`
+    Calendar,
+    /// ISO-8601 ordinal date, e.g. `2024-003`
+    Ordinal,
+    /// ISO-8601 week date, e.g. `2024-W01-3`
+    ///
+    /// Note: the week-numbering year can differ from the calendar year near January 1st
+    Week,
+}
+
+/// Defaults to `Calendar`
+impl Default for DateStyle {
+    fn default() -> Self {
+        Self::Calendar
+    }
+}
+
+// days since 1970-01-01 to (year, month, day)
+// see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+// (year, month, day) to days since 1970-01-01
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// 1-based day of the year
+fn ordinal(days: i64, year: i64) -> i64 {
+    days - days_from_civil(year, 1, 1) + 1
+}
+
+// ISO weekday, Monday is 1 and Sunday is 7. 1970-01-01 was a Thursday
+fn weekday(days: i64) -> i64 {
+    (days + 3).rem_euclid(7) + 1
+}
+
+// number of ISO weeks in a year, either 52 or 53
+fn weeks_in_year(year: i64) -> i64 {
+    let p = |y: i64| (y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400)).rem_euclid(7);
+    if p(year) == 4 || p(year - 1) == 3 {
+        53
+    } else {
+        52
+    }
+}
+
+// (week-numbering year, week, weekday)
+fn iso_week(days: i64, year: i64) -> (i64, i64, i64) {
+    let weekday = weekday(days);
+    match (ordinal(days, year) - weekday + 10) / 7 {
+        0 => (year - 1, weeks_in_year(year - 1), weekday),
+        week if week > weeks_in_year(year) => (year + 1, 1, weekday),
+        week => (year, week, weekday),
+    }
+}
 
 /// A running epoch counter
 ///
@@ -66,6 +649,7 @@ impl FormatTime for Timestamp {
 pub struct Uptime {
     epoch: std::time::Instant,
     style: TimestampStyle,
+    min_width: usize,
 }
 
 impl Uptime {
@@ -76,30 +660,91 @@ impl Uptime {
 
     /// Create an Uptime starting at a specific point in time
     pub fn new(epoch: std::time::Instant, style: TimestampStyle) -> Self {
-        Self { epoch, style }
+        Self {
+            epoch,
+            style,
+            min_width: 0,
+        }
     }
-}
 
-impl FormatTime for Uptime {
-    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
-        let elapsed = self.epoch.elapsed();
-        match self.style {
-            TimestampStyle::Whole => write!(w, "{}s", elapsed.as_secs()),
-            TimestampStyle::Fractional(d) => write!(
+    /// Create an Uptime starting at `now`, printing whole seconds
+    pub fn seconds() -> Self {
+        Self::now(TimestampStyle::Whole)
+    }
+
+    /// Create an Uptime starting at `now`, printing milliseconds (**3** fractional digits)
+    pub fn millis() -> Self {
+        Self::now(TimestampStyle::Millis)
+    }
+
+    /// Create an Uptime starting at `now`, printing microseconds (**6** fractional digits)
+    pub fn micros() -> Self {
+        Self::now(TimestampStyle::Micros)
+    }
+
+    /// Create an Uptime starting at `now`, printing e.g. `1h02m03s` or `45ms`
+    pub fn human() -> Self {
+        Self::now(TimestampStyle::Human)
+    }
+
+    /// Zero-pad the whole seconds to at least `width` digits, keeping the column aligned
+    pub fn with_min_width(mut self, width: usize) -> Self {
+        self.min_width = width;
+        self
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        elapsed: std::time::Duration,
+    ) -> std::io::Result<()> {
+        let (secs, width) = (elapsed.as_secs(), self.min_width);
+        match (self.style, self.style.digits()) {
+            (TimestampStyle::Human, _) => {
+                let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+                match () {
+                    _ if hours > 0 => write!(w, "{}h{:02}m{:02}s", hours, minutes, secs),
+                    _ if minutes > 0 => write!(w, "{}m{:02}s", minutes, secs),
+                    _ if secs > 0 => write!(w, "{}s", secs),
+                    _ => write!(w, "{}ms", elapsed.subsec_millis()),
+                }
+            }
+            (_, 0) => write!(w, "{:0width$}s", secs, width = width),
+            (_, digits) => write!(
                 w,
-                "{}.{}s",
-                elapsed.as_secs(),
-                scale(elapsed.subsec_nanos(), d)
+                "{:0width$}.{}s",
+                secs,
+                scale(elapsed.subsec_nanos(), digits),
+                width = width
             ),
         }
     }
 }
 
+impl FormatTime for Uptime {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.format_at(w, self.epoch.elapsed())
+    }
+
+    /// The uptime at `time`, which is clamped to the epoch
+    fn format_time_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        time: std::time::SystemTime,
+    ) -> std::io::Result<()> {
+        let age = std::time::SystemTime::now()
+            .duration_since(time)
+            .unwrap_or_default();
+        self.format_at(w, self.epoch.elapsed().saturating_sub(age))
+    }
+}
+
 impl From<std::time::Instant> for Uptime {
     fn from(epoch: std::time::Instant) -> Self {
         Self {
             epoch,
             style: TimestampStyle::Fractional(9),
+            min_width: 0,
         }
     }
 }
@@ -110,10 +755,45 @@ impl Default for Uptime {
         Self {
             epoch: std::time::Instant::now(),
             style: TimestampStyle::Fractional(9),
+            min_width: 0,
         }
     }
 }
 
+/// The time elapsed since the previous record
+///
+/// This prints e.g. `+0.013s`, the first record prints `+0.000s`
+#[derive(Default)]
+pub struct Delta {
+    last: std::sync::Mutex<Option<std::time::Instant>>,
+}
+
+impl Delta {
+    /// Create a Delta, starting at the next record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn format_at(
+        &self,
+        w: &mut dyn std::io::Write,
+        now: std::time::Instant,
+    ) -> std::io::Result<()> {
+        let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+        let elapsed = last
+            .replace(now)
+            .map(|last| now.saturating_duration_since(last))
+            .unwrap_or_default();
+        write!(w, "+{}.{:03}s", elapsed.as_secs(), elapsed.subsec_millis())
+    }
+}
+
+impl FormatTime for Delta {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.format_at(w, std::time::Instant::now())
+    }
+}
+
 /// Timestamp style to determine how timestamps should be printed
 #[derive(Copy, Clone, Debug)]
 #[non_exhaustive]
@@ -122,6 +802,30 @@ pub enum TimestampStyle {
     Whole,
     /// Include fractional time, up to `n` digits
     Fractional(usize),
+    /// Elapsed time by magnitude, like `1h02m03s`, `2m03s`, `3s` or `45ms`
+    ///
+    /// This is meant for [`Uptime`](./struct.Uptime.html), the wall-clock formatters print
+    /// whole seconds with it
+    Human,
+    /// Milliseconds, the same as `Fractional(3)`
+    Millis,
+    /// Microseconds, the same as `Fractional(6)`
+    Micros,
+    /// Nanoseconds, the same as `Fractional(9)`
+    Nanos,
+}
+
+impl TimestampStyle {
+    // how many fractional digits are printed
+    fn digits(self) -> usize {
+        match self {
+            Self::Whole | Self::Human => 0,
+            Self::Fractional(digits) => digits,
+            Self::Millis => 3,
+            Self::Micros => 6,
+            Self::Nanos => 9,
+        }
+    }
 }
 
 /// Defaults to `Whole`
@@ -131,10 +835,485 @@ impl Default for TimestampStyle {
     }
 }
 
+/// Parses a style name, ignoring case
+///
+/// The names are `whole` (or `s`), `millis` (or `ms`), `micros` (or `us`), `nanos` (or `ns`),
+/// `human`, or a number of fractional digits like `2`
+impl std::str::FromStr for TimestampStyle {
+    type Err = ParseTimestampStyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const NAMES: &[(&str, TimestampStyle)] = &[
+            ("whole", TimestampStyle::Whole),
+            ("s", TimestampStyle::Whole),
+            ("millis", TimestampStyle::Millis),
+            ("ms", TimestampStyle::Millis),
+            ("micros", TimestampStyle::Micros),
+            ("us", TimestampStyle::Micros),
+            ("nanos", TimestampStyle::Nanos),
+            ("ns", TimestampStyle::Nanos),
+            ("human", TimestampStyle::Human),
+        ];
+
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|&(_, style)| style)
+            .or_else(|| s.parse().ok().map(TimestampStyle::Fractional))
+            .ok_or_else(|| ParseTimestampStyleError(s.to_string()))
+    }
+}
+
+/// An error returned when a timestamp style could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseTimestampStyleError(String);
+
+impl std::fmt::Display for ParseTimestampStyleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown timestamp style: `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseTimestampStyleError {}
+
+// the first `digits` digits (at most 9) of the nanoseconds, zero-padded
+//
+// this truncates, rounding up could carry into the seconds
 #[inline]
-fn scale(d: u32, s: usize) -> u32 {
-    if s > 9 {
-        return d;
+fn scale(nanos: u32, digits: usize) -> Fraction {
+    Fraction {
+        nanos,
+        digits: digits.min(9),
+    }
+}
+
+struct Fraction {
+    nanos: u32,
+    digits: usize,
+}
+
+impl std::fmt::Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.digits == 0 {
+            return Ok(());
+        }
+        let value = self.nanos / 10_u32.pow(9 - self.digits as u32);
+        write!(f, "{:0digits$}", value, digits = self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(date: DateStyle, secs: u64) -> String {
+        let mut buf = vec![];
+        Utc::new(date).format_secs(&mut buf, secs).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn min_width() {
+        let format = |time: &dyn Fn(&mut dyn std::io::Write) -> std::io::Result<()>| {
+            let mut buffer = vec![];
+            time(&mut buffer).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        let elapsed = std::time::Duration::from_millis(42_500);
+
+        let timestamp = Timestamp::new(TimestampStyle::Whole).with_min_width(5);
+        assert_eq!(format(&|w| timestamp.format_at(w, elapsed)), "00042");
+        let timestamp = Timestamp::new(TimestampStyle::Fractional(1)).with_min_width(3);
+        assert_eq!(format(&|w| timestamp.format_at(w, elapsed)), "042.5");
+        let timestamp = Timestamp::new(TimestampStyle::Whole).with_min_width(1);
+        assert_eq!(format(&|w| timestamp.format_at(w, elapsed)), "42");
+
+        let uptime = Uptime::seconds().with_min_width(4);
+        assert_eq!(format(&|w| uptime.format_at(w, elapsed)), "0042s");
+        let uptime = Uptime::now(TimestampStyle::Fractional(1)).with_min_width(4);
+        assert_eq!(format(&|w| uptime.format_at(w, elapsed)), "0042.5s");
+    }
+
+    #[test]
+    fn scale() {
+        for (nanos, digits, expected) in &[
+            (5_000_000, 3, "005"),
+            (50_000_000, 3, "050"),
+            (500_000_000, 1, "5"),
+            (999_999_999, 2, "99"),
+            (999_999_999, 9, "999999999"),
+            (1, 9, "000000001"),
+            (1, 12, "000000001"),
+            (0, 6, "000000"),
+            (123_456_789, 0, ""),
+        ] {
+            assert_eq!(
+                super::scale(*nanos, *digits).to_string(),
+                *expected,
+                "{} {}",
+                nanos,
+                digits
+            );
+        }
+
+        let format = |time: &dyn Fn(&mut dyn std::io::Write) -> std::io::Result<()>| {
+            let mut buffer = vec![];
+            time(&mut buffer).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        let elapsed = std::time::Duration::from_millis(42_050);
+        let timestamp = Timestamp::new(TimestampStyle::Fractional(3));
+        assert_eq!(format(&|w| timestamp.format_at(w, elapsed)), "42.050");
+        let uptime = Uptime::now(TimestampStyle::Fractional(3));
+        assert_eq!(format(&|w| uptime.format_at(w, elapsed)), "42.050s");
+        let uptime = Uptime::now(TimestampStyle::Fractional(0));
+        assert_eq!(format(&|w| uptime.format_at(w, elapsed)), "42s");
+    }
+
+    #[test]
+    fn precision_units() {
+        let format = |style| {
+            let mut buffer = vec![];
+            Timestamp::new(style)
+                .format_at(&mut buffer, std::time::Duration::new(42, 123_456_789))
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        assert_eq!(format(TimestampStyle::Millis), "42.123");
+        assert_eq!(format(TimestampStyle::Micros), "42.123456");
+        assert_eq!(format(TimestampStyle::Nanos), "42.123456789");
+
+        let mut buffer = vec![];
+        Uptime::now(TimestampStyle::Micros)
+            .format_at(&mut buffer, std::time::Duration::new(42, 123_456_789))
+            .unwrap();
+        assert_eq!(buffer, b"42.123456s");
+
+        for (input, expected) in &[("whole", 0), ("MS", 3), ("micros", 6), ("ns", 9), ("2", 2)] {
+            let style = input.parse::<TimestampStyle>().unwrap();
+            assert_eq!(style.digits(), *expected, "{}", input);
+        }
+        assert!(matches!("human".parse(), Ok(TimestampStyle::Human)));
+        assert_eq!(
+            "hours".parse::<TimestampStyle>().err(),
+            Some(ParseTimestampStyleError("hours".into()))
+        );
+    }
+
+    #[test]
+    fn human_uptime() {
+        let epoch = std::time::Instant::now();
+        let uptime = Uptime::new(epoch, TimestampStyle::Human);
+        let format = |millis| {
+            let mut buffer = vec![];
+            uptime
+                .format_at(&mut buffer, std::time::Duration::from_millis(millis))
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(format(0), "0ms");
+        assert_eq!(format(45), "45ms");
+        assert_eq!(format(999), "999ms");
+        assert_eq!(format(3_250), "3s");
+        assert_eq!(format(123_000), "2m03s");
+        assert_eq!(format(3_600_000), "1h00m00s");
+        assert_eq!(format(3_723_000), "1h02m03s");
+        assert_eq!(format(100 * 3_600_000 + 59_000), "100h00m59s");
+
+        let mut buffer = vec![];
+        Timestamp::new(TimestampStyle::Human)
+            .format_at(&mut buffer, std::time::Duration::from_millis(3_250))
+            .unwrap();
+        assert_eq!(buffer, b"3");
+    }
+
+    #[test]
+    fn format_time_at() {
+        let format = |time: &dyn FormatTime, at| {
+            let mut buffer = vec![];
+            time.format_time_at(&mut buffer, at).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        let at = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1717245296);
+
+        assert_eq!(format(&Utc::default(), at), "2024-06-01T12:34:56Z");
+        assert_eq!(format(&Timestamp::default(), at), "1717245296");
+        assert_eq!(
+            format(&std::sync::Arc::new(Timestamp::default()), at),
+            "1717245296"
+        );
+        let fixed = |w: &mut dyn std::io::Write| write!(w, "now");
+        assert_eq!(format(&fixed, at), "now");
+
+        let epoch = std::time::Instant::now() - std::time::Duration::from_secs(100);
+        let uptime = Uptime::new(epoch, TimestampStyle::Whole);
+        let ago = std::time::SystemTime::now() - std::time::Duration::from_secs(30);
+        assert_eq!(format(&uptime, ago), "70s");
+        assert_eq!(format(&uptime, std::time::UNIX_EPOCH), "0s");
+    }
+
+    #[test]
+    fn delta() {
+        let delta = Delta::new();
+        let start = std::time::Instant::now();
+        let format = |offset: u64| {
+            let mut buf = vec![];
+            let now = start + std::time::Duration::from_millis(offset);
+            delta.format_at(&mut buf, now).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        assert_eq!(format(0), "+0.000s");
+        assert_eq!(format(13), "+0.013s");
+        assert_eq!(format(2513), "+2.500s");
+        assert_eq!(format(2513), "+0.000s");
+    }
+
+    #[test]
+    fn shared() {
+        #[derive(Default)]
+        struct Counter(std::sync::atomic::AtomicUsize);
+        impl FormatTime for Counter {
+            fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+                let n = self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                write!(w, "{}", n + 1)
+            }
+        }
+
+        let counter = std::sync::Arc::new(Counter::default());
+        let owners: [Box<dyn FormatTime>; 2] = [Box::new(counter.clone()), Box::new(counter)];
+
+        let mut buf = vec![];
+        for owner in owners.iter().chain(owners.iter()) {
+            owner.format_time(&mut buf).unwrap();
+        }
+        assert_eq!(buf, b"1234");
+    }
+
+    #[test]
+    fn utc_calendar() {
+        assert_eq!(utc(DateStyle::Calendar, 0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc(DateStyle::Calendar, 1717245296), "2024-06-01T12:34:56Z");
+        assert_eq!(utc(DateStyle::Calendar, 1735689599), "2024-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn utc_fractional() {
+        let format = |style, nanos| {
+            let mut buffer = vec![];
+            let at = std::time::UNIX_EPOCH + std::time::Duration::new(1717245296, nanos);
+            Utc::default()
+                .with_style(style)
+                .format_time_at(&mut buffer, at)
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let style = TimestampStyle::Fractional(3);
+        assert_eq!(format(style, 789_000_000), "2024-06-01T12:34:56.789Z");
+        assert_eq!(format(style, 50_000_000), "2024-06-01T12:34:56.050Z");
+        assert_eq!(format(style, 999), "2024-06-01T12:34:56.000Z");
+        let style = TimestampStyle::Fractional(12);
+        assert_eq!(format(style, 1), "2024-06-01T12:34:56.000000001Z");
+        assert_eq!(
+            format(TimestampStyle::Fractional(0), 789_000_000),
+            "2024-06-01T12:34:56Z"
+        );
+        assert_eq!(
+            format(TimestampStyle::Whole, 789_000_000),
+            "2024-06-01T12:34:56Z"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn local_time() {
+        let format = |local: &LocalTime, nanos| {
+            let mut buffer = vec![];
+            let at = std::time::UNIX_EPOCH + std::time::Duration::new(1717245296, nanos);
+            local.format_time_at(&mut buffer, at).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let offset = |h, m| ::time::UtcOffset::from_hms(h, m, 0).ok();
+        let mut local = LocalTime::new(TimestampStyle::Fractional(3));
+        local.offset = offset(2, 0);
+        assert_eq!(format(&local, 789_000_000), "2024-06-01T14:34:56.789+02:00");
+        local.offset = offset(-9, -30);
+        assert_eq!(format(&local, 789_000_000), "2024-06-01T03:04:56.789-09:30");
+        local.offset = offset(0, 0);
+        assert_eq!(format(&local, 0), "2024-06-01T12:34:56.000+00:00");
+        local.offset = None;
+        assert_eq!(format(&local, 0), "2024-06-01T12:34:56.000Z");
+
+        // whatever the offset here is, the output has the same shape
+        let local = LocalTime::default();
+        let output = format(&local, 0);
+        let (date_time, offset) = output.split_at(19);
+        assert_eq!(date_time.len(), "2024-06-01T12:34:56".len());
+        assert!(date_time.starts_with("2024-0"), "{}", output);
+        match offset.as_bytes() {
+            [b'Z'] => assert!(!local.is_local()),
+            [b'+' | b'-', _, _, b':', _, _] => assert!(local.is_local()),
+            _ => panic!("not a local time: {}", output),
+        }
+    }
+
+    #[test]
+    fn rfc3339() {
+        // `YYYY-MM-DDTHH:MM:SS[.f+]Z`, with `digits` fractional digits
+        fn is_rfc3339(s: &str, digits: usize) -> bool {
+            let digit = |c: u8| c.is_ascii_digit();
+            let b = s.as_bytes();
+            let fraction = match digits {
+                0 => b.get(19..b.len() - 1) == Some(&[]),
+                n => {
+                    b.get(19) == Some(&b'.')
+                        && b.get(20..20 + n)
+                            .is_some_and(|f| f.iter().all(|&c| digit(c)))
+                }
+            };
+            b.len() == 20 + digits + (digits > 0) as usize
+                && [0, 1, 2, 3, 5, 6, 8, 9, 11, 12, 14, 15, 17, 18]
+                    .iter()
+                    .all(|&i| digit(b[i]))
+                && (b[4], b[7], b[10], b[13], b[16]) == (b'-', b'-', b'T', b':', b':')
+                && fraction
+                && s.ends_with('Z')
+        }
+
+        for digits in 0..=9 {
+            let mut buffer = vec![];
+            Rfc3339::new(TimestampStyle::Fractional(digits))
+                .format_time(&mut buffer)
+                .unwrap();
+            let output = String::from_utf8(buffer).unwrap();
+            assert!(is_rfc3339(&output, digits), "{}: {}", digits, output);
+        }
+
+        let mut buffer = vec![];
+        let at = std::time::UNIX_EPOCH + std::time::Duration::new(1717245296, 789_000_000);
+        Rfc3339::new(TimestampStyle::Fractional(3))
+            .format_time_at(&mut buffer, at)
+            .unwrap();
+        assert_eq!(buffer, b"2024-06-01T12:34:56.789Z");
+    }
+
+    #[test]
+    fn strftime() {
+        let format = |fmt: &str| {
+            let mut buffer = vec![];
+            let at = std::time::UNIX_EPOCH + std::time::Duration::new(1717245296, 5_789_000);
+            StrftimeTime::new(fmt)
+                .unwrap()
+                .format_time_at(&mut buffer, at)
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(format("%H:%M:%S%.3f"), "12:34:56.005");
+        assert_eq!(
+            format("%Y-%m-%dT%H:%M:%S%.6fZ"),
+            "2024-06-01T12:34:56.005789Z"
+        );
+        assert_eq!(format("%F %T %z %Z"), "2024-06-01 12:34:56 +0000 UTC");
+        assert_eq!(
+            format("[%e/%b/%y day %j] %s.%f"),
+            "[ 1/Jun/24 day 153] 1717245296.005789000"
+        );
+        assert_eq!(format("100%% at %2f"), "100% at 00");
+        assert_eq!(format(""), "");
+
+        for (fmt, spec) in &[
+            ("%H:%Q", "%Q"),
+            ("%H:%M:%", "%"),
+            ("%.f", "%.f"),
+            ("%.0f", "%.0f"),
+            ("%3x", "%3x"),
+            ("%.3", "%.3"),
+        ] {
+            assert_eq!(
+                StrftimeTime::new(*fmt).err(),
+                Some(TimeFormatError(spec.to_string())),
+                "{}",
+                fmt
+            );
+        }
+        assert_eq!(
+            StrftimeTime::new("%Q").err().map(|err| err.to_string()),
+            Some("unknown time format specifier: `%Q`".to_string())
+        );
+    }
+
+    #[test]
+    fn utc_cache() {
+        let cached = |utc: &Utc, secs| {
+            let mut buffer = vec![];
+            utc.format_date_time(&mut buffer, secs).unwrap();
+            buffer
+        };
+        let uncached = |utc: &Utc, secs| {
+            let mut buffer = vec![];
+            utc.write_date_time(&mut buffer, secs).unwrap();
+            buffer
+        };
+
+        for date in &[DateStyle::Calendar, DateStyle::Ordinal, DateStyle::Week] {
+            let utc = Utc::new(*date);
+            for &secs in &[0, 0, 1717245296, 1717245296, 1717245297, 0, 1735689599] {
+                assert_eq!(cached(&utc, secs), uncached(&utc, secs), "{}", secs);
+            }
+
+            // with the cache in use, it's formatted directly
+            let guard = utc.cache.lock().unwrap();
+            assert_eq!(cached(&utc, 1704067200), uncached(&utc, 1704067200));
+            drop(guard);
+        }
+
+        let utc = Utc::default().with_style(TimestampStyle::Millis);
+        for (nanos, expected) in &[
+            (1_000_000, "2024-06-01T12:34:56.001Z"),
+            (2_000_000, "2024-06-01T12:34:56.002Z"),
+        ] {
+            let mut buffer = vec![];
+            let at = std::time::UNIX_EPOCH + std::time::Duration::new(1717245296, *nanos);
+            utc.format_time_at(&mut buffer, at).unwrap();
+            assert_eq!(String::from_utf8(buffer).unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn common_log() {
+        let format = |secs| {
+            let mut buffer = vec![];
+            Utc::format_common_log(&mut buffer, secs).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        assert_eq!(format(0), "01/Jan/1970:00:00:00 +0000");
+        assert_eq!(format(1717245296), "01/Jun/2024:12:34:56 +0000");
+        assert_eq!(format(1735689599), "31/Dec/2024:23:59:59 +0000");
+    }
+
+    #[test]
+    fn utc_ordinal() {
+        assert_eq!(utc(DateStyle::Ordinal, 1704067200), "2024-001T00:00:00Z");
+        assert_eq!(utc(DateStyle::Ordinal, 1704240000), "2024-003T00:00:00Z");
+        // leap year
+        assert_eq!(utc(DateStyle::Ordinal, 1735689599), "2024-366T23:59:59Z");
+    }
+
+    #[test]
+    fn utc_week() {
+        assert_eq!(utc(DateStyle::Week, 1704067200), "2024-W01-1T00:00:00Z");
+        assert_eq!(utc(DateStyle::Week, 1704240000), "2024-W01-3T00:00:00Z");
+
+        // calendar year ahead of the week-numbering year
+        assert_eq!(utc(DateStyle::Week, 1609459200), "2020-W53-5T00:00:00Z");
+        assert_eq!(utc(DateStyle::Week, 1262476800), "2009-W53-7T00:00:00Z");
+
+        // calendar year behind the week-numbering year
+        assert_eq!(utc(DateStyle::Week, 1735516800), "2025-W01-1T00:00:00Z");
+        assert_eq!(utc(DateStyle::Week, 1230508800), "2009-W01-1T00:00:00Z");
     }
-    d / 10_usize.pow(9_usize.saturating_sub(s) as u32) as u32
 }
@@ -2,15 +2,33 @@ use crate::format::time::FormatTime;
 use std::io::Write;
 
 use super::color::RecordColorConfig;
+use super::fmt::FmtWriter;
+use super::level::LevelLabels;
 
 #[cfg(feature = "color")]
-use termcolor::{ColorSpec, WriteColor};
+use termcolor::WriteColor;
 
 /// A record writer
+#[derive(Copy, Clone)]
 pub struct Writer<'a, 'b: 'a> {
     #[allow(dead_code)]
     record_colors: RecordColorConfig,
     record: &'a log::Record<'b>,
+    separator: &'a str,
+    target_brackets: (&'a str, &'a str),
+    target_width: usize,
+    max_target_len: usize,
+    level_labels: LevelLabels,
+    escape_control: bool,
+    indent: usize,
+    message_continuation: Option<&'a str>,
+    crlf: bool,
+    #[allow(dead_code)]
+    level_colored_continuation: bool,
+    #[allow(dead_code)]
+    level_colored_message: bool,
+    #[cfg_attr(not(feature = "kv"), allow(dead_code))]
+    key_values: bool,
 }
 
 /// Wrap a record with a writer
@@ -21,18 +39,192 @@ pub fn new_writer<'a, 'b: 'a>(
     Writer {
         record_colors: record_colors.into().unwrap_or_default(),
         record,
+        separator: " ",
+        target_brackets: ("[", "]"),
+        target_width: 0,
+        max_target_len: 0,
+        level_labels: LevelLabels::default(),
+        escape_control: false,
+        indent: 0,
+        message_continuation: None,
+        crlf: false,
+        level_colored_continuation: false,
+        level_colored_message: false,
+        key_values: true,
+    }
+}
+
+impl<'a, 'b: 'a> Writer<'a, 'b> {
+    /// Use this separator between the fields, instead of a single space
+    ///
+    /// The target keeps its brackets, the separator goes outside of them
+    pub fn with_separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Surround the target with these, instead of `[` and `]`
+    pub fn with_target_brackets(mut self, open: &'a str, close: &'a str) -> Self {
+        self.target_brackets = (open, close);
+        self
+    }
+
+    /// Fit the target to `width` columns, padding short ones and truncating long ones with `…`
+    ///
+    /// The padding goes after the closing bracket. `0` writes the target as it is (the default)
+    pub fn with_target_width(mut self, width: usize) -> Self {
+        self.target_width = width;
+        self
+    }
+
+    /// Cut targets longer than `len` columns, keeping their end, e.g. `…::io::driver`
+    ///
+    /// This happens before fitting the target to its width. `0` keeps the whole target (the default)
+    pub fn with_max_target_len(mut self, len: usize) -> Self {
+        self.max_target_len = len;
+        self
+    }
+
+    /// Use these labels for the level
+    pub fn with_level_labels(mut self, level_labels: LevelLabels) -> Self {
+        self.level_labels = level_labels;
+        self
+    }
+
+    /// Escape control characters in the message, e.g. `ESC` is written as `\x1b` and `CSI` as
+    /// `\u{9b}`
+    ///
+    /// Newlines and tabs are kept as-is
+    pub fn with_control_char_escaping(mut self, escape: bool) -> Self {
+        self.escape_control = escape;
+        self
+    }
+
+    /// Start every line of a multi-line message after the first with `cont` and the separator
+    ///
+    /// A trailing newline doesn't start another line, and `\r\n` is treated like `\n`
+    pub fn with_message_continuation(mut self, cont: &'a str) -> Self {
+        self.message_continuation.replace(cont);
+        self
+    }
+
+    /// Color the continuation marker with the level's color, instead of its own color
+    pub fn with_level_colored_continuation(mut self, level_colored: bool) -> Self {
+        self.level_colored_continuation = level_colored;
+        self
+    }
+
+    /// Color the message with the level's color, instead of its own color
+    pub fn with_level_colored_message(mut self, level_colored: bool) -> Self {
+        self.level_colored_message = level_colored;
+        self
+    }
+
+    /// Write the record's key-values after the message, as `key=value` (the default)
+    #[cfg(feature = "kv")]
+    pub fn with_key_values(mut self, key_values: bool) -> Self {
+        self.key_values = key_values;
+        self
+    }
+
+    /// End lines with `\r\n` instead of `\n`, including the newlines in the message
+    pub fn with_crlf(mut self, crlf: bool) -> Self {
+        self.crlf = crlf;
+        self
+    }
+
+    /// Indent every line of the message after the first by this many spaces
+    pub fn with_message_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
     }
 }
 
 impl<'a, 'b: 'a> Writer<'a, 'b> {
+    // the parts without colors or escaping are shared with the `core::fmt` writer
+    fn fmt_writer(&self) -> FmtWriter<'a, 'b> {
+        super::fmt::new_fmt_writer(self.record)
+            .with_separator(self.separator)
+            .with_level_labels(self.level_labels)
+    }
+
     #[inline(always)]
     fn inner_level<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
-        write!(buffer, "{:<5}", self.record.level())
+        IoFmt::write(buffer, |w| self.fmt_writer().level(w))
+    }
+
+    #[inline(always)]
+    fn inner_gutter<W: ?Sized + Write>(
+        &self,
+        buffer: &mut W,
+        symbols: &LevelLabels,
+    ) -> std::io::Result<()> {
+        write!(
+            buffer,
+            "{:<width$}",
+            symbols.label_for(self.record.level()),
+            width = symbols.width()
+        )
     }
 
     #[inline(always)]
     fn inner_target<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
-        write!(buffer, "{}", self.record.target())
+        match (self.target_width, self.max_target_len) {
+            (0, 0) => IoFmt::write(buffer, |w| {
+                self.fmt_writer().with_target_brackets("", "").target(w)
+            }),
+            (0, _) => write!(buffer, "{}", self.shortened_target()),
+            _ => write!(buffer, "{}", self.fitted_target().0),
+        }
+    }
+
+    // the end of the target, when it's longer than `max_target_len` columns
+    fn shortened_target(&self) -> std::borrow::Cow<'_, str> {
+        let target = self.record.target();
+        if self.max_target_len == 0 || str_width(target) <= self.max_target_len {
+            return target.into();
+        }
+
+        let room = self.max_target_len - 1;
+        let mut used = 0;
+        let start = target
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| {
+                used += char_width(ch);
+                used <= room
+            })
+            .last()
+            .map_or(target.len(), |(i, _)| i);
+        format!("…{}", &target[start..]).into()
+    }
+
+    // the target cut to `target_width` columns, and the padding needed to fill them
+    fn fitted_target(&self) -> (std::borrow::Cow<'_, str>, usize) {
+        let target = self.shortened_target();
+        let width = str_width(&target);
+        if width <= self.target_width {
+            return (target, self.target_width - width);
+        }
+
+        let room = self.target_width - 1;
+        let (mut fitted, mut used) = (String::new(), 0);
+        for ch in target.chars() {
+            if used + char_width(ch) > room {
+                break;
+            }
+            used += char_width(ch);
+            fitted.push(ch);
+        }
+        fitted.push('…');
+        (fitted.into(), room - used)
+    }
+
+    fn inner_target_padding<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        match self.target_width {
+            0 => Ok(()),
+            _ => write!(buffer, "{:pad$}", "", pad = self.fitted_target().1),
+        }
     }
 
     #[inline(always)]
@@ -41,10 +233,19 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
         mut buffer: &mut W,
         time: &T,
     ) -> std::io::Result<()> {
-        write!(buffer, " ")?;
         time.format_time(&mut buffer)
     }
 
+    #[inline(always)]
+    fn inner_separator<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        IoFmt::write(buffer, |w| self.fmt_writer().separator(w))
+    }
+
+    #[inline(always)]
+    fn inner_newline<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        buffer.write_all(if self.crlf { b"\r\n" } else { b"\n" })
+    }
+
     #[inline(always)]
     fn inner_continuation<W: ?Sized + Write>(
         &self,
@@ -56,11 +257,88 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
 
     #[inline(always)]
     fn inner_message<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
-        write!(buffer, " {}", self.record.args())
+        if self.crlf {
+            return self.inner_message_lines(&mut Crlf::new(buffer));
+        }
+        self.inner_message_lines(buffer)
+    }
+
+    #[inline(always)]
+    fn inner_message_lines<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        match (self.escape_control, self.indent) {
+            (false, 0) => self.inner_message_raw(buffer),
+            (true, 0) => self.inner_message_raw(&mut EscapeControl(buffer)),
+            (false, indent) => self.inner_message_raw(&mut Indent::new(buffer, indent)),
+            (true, indent) => {
+                self.inner_message_raw(&mut EscapeControl(&mut Indent::new(buffer, indent)))
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn inner_message_raw<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        write!(buffer, "{}", self.record.args())?;
+        #[cfg(feature = "kv")]
+        if self.key_values {
+            self.inner_key_values(buffer)?;
+        }
+        Ok(())
+    }
+
+    // only the first value of a key is written, so a record's own pairs win over the context's.
+    // values that are empty, or have spaces, `=` or `"` in them, are quoted
+    #[cfg(feature = "kv")]
+    fn inner_key_values<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        struct Visit<'a, 'kvs, W: ?Sized> {
+            buffer: &'a mut W,
+            seen: Vec<log::kv::Key<'kvs>>,
+            value: String,
+        }
+        impl<'kvs, W: ?Sized + Write> log::kv::VisitSource<'kvs> for Visit<'_, 'kvs, W> {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                if self.seen.contains(&key) {
+                    return Ok(());
+                }
+
+                use std::fmt::Write as _;
+                self.value.clear();
+                write!(self.value, "{}", value)?;
+                let quote = self.value.is_empty()
+                    || self
+                        .value
+                        .contains(|c: char| c.is_whitespace() || c == '=' || c == '"');
+                match quote {
+                    true => write!(self.buffer, " {}={:?}", key, self.value)?,
+                    false => write!(self.buffer, " {}={}", key, self.value)?,
+                }
+                self.seen.push(key);
+                Ok(())
+            }
+        }
+
+        self.record
+            .key_values()
+            .visit(&mut Visit {
+                buffer,
+                seen: vec![],
+                value: String::new(),
+            })
+            .map_err(std::io::Error::other)
     }
 }
 
 impl<'a, 'b: 'a> Writer<'a, 'b> {
+    /// Write the separator
+    ///
+    /// This goes between two fields, it is never written before the first one
+    pub fn separator<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_separator(buffer)
+    }
+
     /// Write the level
     #[cfg(not(feature = "color"))]
     pub fn level<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
@@ -70,35 +348,130 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
     /// Write the level
     #[cfg(feature = "color")]
     pub fn level<W: ?Sized + Write + WriteColor>(&self, buffer: &mut W) -> std::io::Result<()> {
-        let color = match self.record.level() {
-            log::Level::Error => self.record_colors.level.error,
-            log::Level::Warn => self.record_colors.level.warn,
-            log::Level::Info => self.record_colors.level.info,
-            log::Level::Debug => self.record_colors.level.debug,
-            log::Level::Trace => self.record_colors.level.trace,
-        };
-
-        buffer.set_color(ColorSpec::new().set_fg(Some(color)))?;
+        let style = self.record_colors.level.style_for(self.record.level());
+        buffer.set_color(&style.to_spec())?;
         self.inner_level(buffer)?;
         buffer.reset()
     }
 
+    /// Write a symbol for the level, padded to the widest symbol
+    #[cfg(not(feature = "color"))]
+    pub fn gutter<W: ?Sized + Write>(
+        &self,
+        buffer: &mut W,
+        symbols: &LevelLabels,
+    ) -> std::io::Result<()> {
+        self.inner_gutter(buffer, symbols)
+    }
+
+    /// Write a symbol for the level, padded to the widest symbol, in the level's color
+    #[cfg(feature = "color")]
+    pub fn gutter<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+        symbols: &LevelLabels,
+    ) -> std::io::Result<()> {
+        let style = self.record_colors.level.style_for(self.record.level());
+        buffer.set_color(&style.to_spec())?;
+        self.inner_gutter(buffer, symbols)?;
+        buffer.reset()
+    }
+
+    /// Write a fixed prefix, e.g. `[worker-3]`, in the target's color
+    #[cfg(not(feature = "color"))]
+    pub fn prefix<W: ?Sized + Write>(&self, buffer: &mut W, prefix: &str) -> std::io::Result<()> {
+        write!(buffer, "{}", prefix)
+    }
+
+    /// Write a fixed prefix, e.g. `[worker-3]`, in the target's color
+    #[cfg(feature = "color")]
+    pub fn prefix<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+        prefix: &str,
+    ) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.target.to_spec())?;
+        write!(buffer, "{}", prefix)?;
+        buffer.reset()
+    }
+
+    /// Write spaces in place of the target, keeping the columns aligned
+    pub fn blank_target<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        let (open, close) = self.target_brackets;
+        let target = match self.target_width {
+            0 => str_width(&self.shortened_target()),
+            width => width,
+        };
+        let width = str_width(open) + target + str_width(close);
+        write!(buffer, "{:width$}", "", width = width)
+    }
+
     /// Write the target
     #[cfg(not(feature = "color"))]
     pub fn target<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
-        write!(buffer, " [")?;
+        write!(buffer, "{}", self.target_brackets.0)?;
         self.inner_target(buffer)?;
-        write!(buffer, "]")
+        write!(buffer, "{}", self.target_brackets.1)?;
+        self.inner_target_padding(buffer)
     }
 
     /// Write the target
     #[cfg(feature = "color")]
     pub fn target<W: ?Sized + Write + WriteColor>(&self, buffer: &mut W) -> std::io::Result<()> {
-        write!(buffer, " [")?;
-        buffer.set_color(ColorSpec::new().set_fg(self.record_colors.target.into()))?;
+        write!(buffer, "{}", self.target_brackets.0)?;
+        let style = self.record_colors.target_style(self.record.target());
+        buffer.set_color(&style.to_spec())?;
         self.inner_target(buffer)?;
         buffer.reset()?;
-        write!(buffer, "]")
+        write!(buffer, "{}", self.target_brackets.1)?;
+        self.inner_target_padding(buffer)
+    }
+
+    /// Write the module path of the log site, or `?` when it isn't known
+    #[cfg(not(feature = "color"))]
+    pub fn module_path<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_module_path(buffer)
+    }
+
+    /// Write the module path of the log site, or `?` when it isn't known
+    #[cfg(feature = "color")]
+    pub fn module_path<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+    ) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.location.to_spec())?;
+        self.inner_module_path(buffer)?;
+        buffer.reset()
+    }
+
+    /// Write the `file:line` of the log site
+    ///
+    /// A missing line leaves out the `:line`, a missing file is written as `?`
+    #[cfg(not(feature = "color"))]
+    pub fn location<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_location(buffer)
+    }
+
+    /// Write the `file:line` of the log site
+    ///
+    /// A missing line leaves out the `:line`, a missing file is written as `?`
+    #[cfg(feature = "color")]
+    pub fn location<W: ?Sized + Write + WriteColor>(&self, buffer: &mut W) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.location.to_spec())?;
+        self.inner_location(buffer)?;
+        buffer.reset()
+    }
+
+    fn inner_module_path<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        write!(buffer, "{}", self.record.module_path().unwrap_or("?"))
+    }
+
+    fn inner_location<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        write!(buffer, "{}", self.record.file().unwrap_or("?"))?;
+        match self.record.line() {
+            Some(line) => write!(buffer, ":{}", line),
+            None => Ok(()),
+        }
     }
 
     // Write the provided timestamp
@@ -118,12 +491,74 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
         buffer: &mut W,
         time: &T,
     ) -> std::io::Result<()> {
-        write!(buffer, " ")?;
-        buffer.set_color(ColorSpec::new().set_fg(self.record_colors.timestamp.into()))?;
+        buffer.set_color(&self.record_colors.timestamp.to_spec())?;
         self.inner_timestamp(buffer, time)?;
         buffer.reset()
     }
 
+    /// Write a sequence number, zero-padded to `width`
+    #[cfg(not(feature = "color"))]
+    pub fn sequence<W: ?Sized + Write>(
+        &self,
+        buffer: &mut W,
+        seq: u64,
+        width: usize,
+    ) -> std::io::Result<()> {
+        write!(buffer, "#{:0width$}", seq, width = width)
+    }
+
+    /// Write a sequence number, zero-padded to `width`
+    #[cfg(feature = "color")]
+    pub fn sequence<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+        seq: u64,
+        width: usize,
+    ) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.sequence.to_spec())?;
+        write!(buffer, "#{:0width$}", seq, width = width)?;
+        buffer.reset()
+    }
+
+    /// Write the current thread's name, or its id (e.g. `ThreadId(2)`) when it has no name
+    #[cfg(not(feature = "color"))]
+    pub fn thread_name<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_thread(buffer, true)
+    }
+
+    /// Write the current thread's name, or its id (e.g. `ThreadId(2)`) when it has no name
+    #[cfg(feature = "color")]
+    pub fn thread_name<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+    ) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.thread.to_spec())?;
+        self.inner_thread(buffer, true)?;
+        buffer.reset()
+    }
+
+    /// Write the current thread's id, e.g. `ThreadId(2)`
+    #[cfg(not(feature = "color"))]
+    pub fn thread_id<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_thread(buffer, false)
+    }
+
+    /// Write the current thread's id, e.g. `ThreadId(2)`
+    #[cfg(feature = "color")]
+    pub fn thread_id<W: ?Sized + Write + WriteColor>(&self, buffer: &mut W) -> std::io::Result<()> {
+        buffer.set_color(&self.record_colors.thread.to_spec())?;
+        self.inner_thread(buffer, false)?;
+        buffer.reset()
+    }
+
+    fn inner_thread<W: ?Sized + Write>(&self, buffer: &mut W, name: bool) -> std::io::Result<()> {
+        let thread = std::thread::current();
+        match thread.name().filter(|_| name) {
+            Some(name) => write!(buffer, "{}", name),
+            None => write!(buffer, "{:?}", thread.id()),
+        }
+    }
+
     /// Write a continuation (and insert a new line)
     #[cfg(not(feature = "color"))]
     pub fn continuation<W: ?Sized + Write>(
@@ -131,7 +566,7 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
         buffer: &mut W,
         cont: &str,
     ) -> std::io::Result<()> {
-        writeln!(buffer)?;
+        self.inner_newline(buffer)?;
         self.inner_continuation(buffer, cont)
     }
 
@@ -142,25 +577,349 @@ impl<'a, 'b: 'a> Writer<'a, 'b> {
         buffer: &mut W,
         cont: &str,
     ) -> std::io::Result<()> {
-        writeln!(buffer)?;
-        buffer.set_color(ColorSpec::new().set_fg(self.record_colors.continuation.into()))?;
+        self.inner_newline(buffer)?;
+        let mut style = self.record_colors.continuation;
+        if self.level_colored_continuation {
+            style.color = self.record_colors.level.color_for(self.record.level());
+        }
+        buffer.set_color(&style.to_spec())?;
         self.inner_continuation(buffer, cont)?;
         buffer.reset()
     }
 
+    /// Write the message, wrapped to `width` columns with `cont` starting each wrapped line
+    ///
+    /// `start` is the column the message starts at. Lines are broken at spaces where possible,
+    /// the message's own newlines are kept and also start with `cont`.
+    #[cfg(not(feature = "color"))]
+    pub fn wrapped_message<W: ?Sized + Write>(
+        &self,
+        buffer: &mut W,
+        start: usize,
+        width: usize,
+        cont: &str,
+    ) -> std::io::Result<()> {
+        let text = self.message_text()?;
+        for (i, line) in self.wrap(&text, start, width, cont).enumerate() {
+            if i > 0 {
+                self.continuation(buffer, cont)?;
+                self.inner_separator(buffer)?;
+            }
+            write!(buffer, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Write the message, wrapped to `width` columns with `cont` starting each wrapped line
+    ///
+    /// `start` is the column the message starts at. Lines are broken at spaces where possible,
+    /// the message's own newlines are kept and also start with `cont`.
+    #[cfg(feature = "color")]
+    pub fn wrapped_message<W: ?Sized + Write + WriteColor>(
+        &self,
+        buffer: &mut W,
+        start: usize,
+        width: usize,
+        cont: &str,
+    ) -> std::io::Result<()> {
+        let text = self.message_text()?;
+        for (i, line) in self.wrap(&text, start, width, cont).enumerate() {
+            if i > 0 {
+                self.continuation(buffer, cont)?;
+                self.inner_separator(buffer)?;
+            }
+            buffer.set_color(&self.message_style().to_spec())?;
+            write!(buffer, "{}", line)?;
+            buffer.reset()?;
+        }
+        Ok(())
+    }
+
+    // without `\r\n` line endings, the callers split the lines and write their own endings
+    fn message_text(&self) -> std::io::Result<String> {
+        let mut text = vec![];
+        self.inner_message_lines(&mut text)?;
+        Ok(String::from_utf8_lossy(&text).into_owned())
+    }
+
+    fn wrap<'t>(
+        &self,
+        text: &'t str,
+        start: usize,
+        width: usize,
+        cont: &str,
+    ) -> impl Iterator<Item = &'t str> {
+        let first = width.saturating_sub(start).max(1);
+        let rest = width
+            .saturating_sub(str_width(cont) + str_width(self.separator))
+            .max(1);
+        wrap_lines(text, first, rest).into_iter()
+    }
+
     /// Write the message
     #[cfg(not(feature = "color"))]
     pub fn message<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
-        self.inner_message(buffer)?;
-        writeln!(buffer)
+        let cont = match self.message_continuation {
+            Some(cont) => cont,
+            None => return self.inner_message(buffer),
+        };
+        let text = self.message_text()?;
+        for (i, line) in message_lines(&text).enumerate() {
+            if i > 0 {
+                self.continuation(buffer, cont)?;
+                self.inner_separator(buffer)?;
+            }
+            write!(buffer, "{}", line)?;
+        }
+        Ok(())
     }
 
     /// Write the message
     #[cfg(feature = "color")]
     pub fn message<W: ?Sized + Write + WriteColor>(&self, buffer: &mut W) -> std::io::Result<()> {
-        buffer.set_color(ColorSpec::new().set_fg(self.record_colors.message.into()))?;
-        self.inner_message(buffer)?;
-        buffer.reset()?;
-        writeln!(buffer)
+        let cont = match self.message_continuation {
+            Some(cont) => cont,
+            None => {
+                buffer.set_color(&self.message_style().to_spec())?;
+                self.inner_message(buffer)?;
+                return buffer.reset();
+            }
+        };
+        let text = self.message_text()?;
+        for (i, line) in message_lines(&text).enumerate() {
+            if i > 0 {
+                self.continuation(buffer, cont)?;
+                self.inner_separator(buffer)?;
+            }
+            buffer.set_color(&self.message_style().to_spec())?;
+            write!(buffer, "{}", line)?;
+            buffer.reset()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "color")]
+    fn message_style(&self) -> super::color::Style {
+        let mut style = self.record_colors.message;
+        if self.level_colored_message {
+            style.color = self.record_colors.level.color_for(self.record.level());
+        }
+        style
+    }
+
+    /// End the record with a new line
+    ///
+    /// This is written once, after the last field, wherever the message was
+    pub fn end<W: ?Sized + Write>(&self, buffer: &mut W) -> std::io::Result<()> {
+        self.inner_newline(buffer)
+    }
+}
+
+/// Adapts an `io::Write` to a `fmt::Write`, keeping the io error
+struct IoFmt<'a, W: ?Sized> {
+    inner: &'a mut W,
+    error: std::io::Result<()>,
+}
+
+impl<'a, W: ?Sized + Write> IoFmt<'a, W> {
+    fn write(
+        inner: &'a mut W,
+        f: impl FnOnce(&mut dyn std::fmt::Write) -> std::fmt::Result,
+    ) -> std::io::Result<()> {
+        let mut this = Self {
+            inner,
+            error: Ok(()),
+        };
+        match f(&mut this) {
+            Ok(()) => Ok(()),
+            Err(..) => this
+                .error
+                .and(Err(std::io::Error::other("formatter error"))),
+        }
+    }
+}
+
+impl<W: ?Sized + Write> std::fmt::Write for IoFmt<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Err(err);
+            std::fmt::Error
+        })
+    }
+}
+
+// the lines of a message, without a line for a trailing newline and without the `\r` of `\r\n`
+fn message_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.strip_suffix('\n')
+        .unwrap_or(text)
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+// splits text into lines of at most `first` columns, then `rest` columns
+fn wrap_lines(text: &str, first: usize, rest: usize) -> Vec<&str> {
+    let mut lines = vec![];
+    let mut avail = first;
+    for mut para in message_lines(text) {
+        loop {
+            if str_width(para) <= avail {
+                lines.push(para);
+                break;
+            }
+
+            let (mut used, mut cut, mut space) = (0, para.len(), None);
+            for (i, ch) in para.char_indices() {
+                if ch == ' ' {
+                    space.replace(i);
+                }
+                used += char_width(ch);
+                if used > avail {
+                    cut = i;
+                    break;
+                }
+            }
+
+            let (line, next) = match space {
+                Some(space) if space > 0 => (&para[..space], para[space..].trim_start_matches(' ')),
+                // a single word wider than the line, always take at least one char
+                _ => para.split_at(cut.max(para.chars().next().map_or(0, char::len_utf8))),
+            };
+            lines.push(line);
+            avail = rest;
+            para = next;
+            if para.is_empty() {
+                break;
+            }
+        }
+        avail = rest;
+    }
+    lines
+}
+
+pub(crate) fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+// best-effort: East Asian wide and fullwidth characters take two columns
+fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x1100..=0x115f
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f900..=0x1f9ff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+struct Indent<'a, W: ?Sized> {
+    inner: &'a mut W,
+    indent: usize,
+    pending: bool,
+}
+
+impl<'a, W: ?Sized> Indent<'a, W> {
+    fn new(inner: &'a mut W, indent: usize) -> Self {
+        Self {
+            inner,
+            indent,
+            pending: false,
+        }
+    }
+}
+
+impl<W: ?Sized + Write> Write for Indent<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // the indent is deferred so a trailing newline doesn't leave a line of just spaces
+        for line in buf.split_inclusive(|&b| b == b'\n') {
+            if std::mem::take(&mut self.pending) {
+                write!(self.inner, "{:indent$}", "", indent = self.indent)?;
+            }
+            self.inner.write_all(line)?;
+            self.pending = line.ends_with(b"\n");
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes `\n` as `\r\n`, leaving an existing `\r\n` alone
+struct Crlf<'a, W: ?Sized> {
+    inner: &'a mut W,
+    cr: bool,
+}
+
+impl<'a, W: ?Sized> Crlf<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        Self { inner, cr: false }
+    }
+}
+
+impl<W: ?Sized + Write> Write for Crlf<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for line in buf.split_inclusive(|&b| b == b'\n') {
+            match line.split_last() {
+                Some((b'\n', head)) => {
+                    self.inner.write_all(head)?;
+                    let cr = head.last().map_or(self.cr, |&b| b == b'\r');
+                    self.inner.write_all(if cr { b"\n" } else { b"\r\n" })?;
+                    self.cr = false;
+                }
+                _ => {
+                    self.inner.write_all(line)?;
+                    self.cr = line.last() == Some(&b'\r');
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct EscapeControl<'a, W: ?Sized>(&'a mut W);
+
+// C0 controls and DEL are written as `\x1b`, C1 controls as `\u{9b}` and invalid UTF-8 bytes as
+// `\xff`, as terminals can act on any of them
+impl<W: ?Sized + Write> Write for EscapeControl<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for chunk in buf.utf8_chunks() {
+            let valid = chunk.valid();
+            let mut start = 0;
+            for (i, ch) in valid.char_indices() {
+                if !ch.is_control() || ch == '\n' || ch == '\t' {
+                    continue;
+                }
+                self.0.write_all(&valid.as_bytes()[start..i])?;
+                match ch {
+                    '\0'..='\x7f' => write!(self.0, "\\x{:02x}", ch as u32)?,
+                    _ => write!(self.0, "\\u{{{:x}}}", ch as u32)?,
+                }
+                start = i + ch.len_utf8();
+            }
+            self.0.write_all(&valid.as_bytes()[start..])?;
+            for b in chunk.invalid() {
+                write!(self.0, "\\x{:02x}", b)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
     }
 }
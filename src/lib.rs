@@ -1,109 +1,730 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
 use once_cell::sync::OnceCell;
 
+#[cfg(feature = "std")]
 static INSTANCE: OnceCell<Logger> = OnceCell::new();
 
-#[doc(inline)]
+#[cfg(feature = "std")]
 pub mod filtered;
 
-#[doc(inline)]
 pub mod format;
 
-#[doc(inline)]
+#[cfg(feature = "std")]
 pub mod logger;
 
+#[cfg(all(unix, feature = "signal-hook"))]
+mod signal;
+#[cfg(all(unix, feature = "signal-hook"))]
+pub use signal::install_sigusr_handler;
+
+#[cfg(feature = "std")]
 struct Logger {
-    fmt: Box<dyn format::Print>,
-    filters: Option<filtered::Filtered>,
-    min_level: log::LevelFilter,
+    fmt: std::sync::RwLock<std::sync::Arc<dyn format::Print>>,
+    formats: std::collections::HashMap<String, std::sync::Arc<dyn format::Print>>,
+    filters: std::sync::Arc<arc_swap::ArcSwapOption<filtered::Filtered>>,
+    min_level: std::sync::atomic::AtomicUsize,
+    on_error: OnError,
+    warned: std::sync::atomic::AtomicBool,
 }
 
+#[cfg(feature = "std")]
 impl Logger {
+    fn min_level(&self) -> log::LevelFilter {
+        let level = self.min_level.load(std::sync::atomic::Ordering::Relaxed);
+        log::LevelFilter::iter()
+            .nth(level)
+            .unwrap_or(log::LevelFilter::Trace)
+    }
+
+    fn set_min_level(&self, min_level: log::LevelFilter) {
+        self.min_level
+            .store(min_level as usize, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn set_filters(&self, filters: Option<filtered::Filtered>) {
+        self.reload_handle().reload(filters)
+    }
+
+    fn update_filters(&self, mut update: impl FnMut(&mut filtered::Filtered)) {
+        self.filters.rcu(|filters| {
+            let mut filters = filters.as_deref().cloned().unwrap_or_default();
+            update(&mut filters);
+            Some(std::sync::Arc::new(filters))
+        });
+    }
+
+    fn reload_handle(&self) -> ReloadHandle {
+        ReloadHandle {
+            filters: std::sync::Arc::clone(&self.filters),
+        }
+    }
+
+    fn set_active_format(&self, name: &str) -> bool {
+        let format = match self.formats.get(name) {
+            Some(format) => std::sync::Arc::clone(format),
+            None => return false,
+        };
+        *self.fmt.write().unwrap_or_else(|err| err.into_inner()) = format;
+        true
+    }
+
     #[inline]
     fn print(&self, record: &log::Record) {
-        let _ = self.fmt.print(record);
+        let fmt = self.fmt.read().unwrap_or_else(|err| err.into_inner());
+
+        #[cfg(feature = "kv")]
+        {
+            if let Some(provider) = CONTEXT.get() {
+                let pairs = provider();
+                let context = Context {
+                    record: record.key_values(),
+                    pairs: &pairs,
+                };
+                let result = fmt.print(
+                    &log::Record::builder()
+                        .args(*record.args())
+                        .metadata(record.metadata().clone())
+                        .module_path(record.module_path())
+                        .file(record.file())
+                        .line(record.line())
+                        .key_values(&context)
+                        .build(),
+                );
+                return self.handle_error(result);
+            }
+        }
+
+        self.handle_error(fmt.print(record))
+    }
+
+    #[inline]
+    fn handle_error(&self, result: std::io::Result<()>) {
+        let err = match result {
+            Err(err) => err,
+            _ => return,
+        };
+
+        match self.on_error {
+            OnError::Ignore => {}
+            OnError::Stderr => {
+                if !self.warned.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                    eprintln!("arbitrary_logger: cannot print record: {}", err)
+                }
+            }
+            OnError::Panic => panic!("arbitrary_logger: cannot print record: {}", err),
+        }
     }
 }
 
+#[cfg(feature = "std")]
 impl log::Log for Logger {
     #[inline(always)]
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        metadata.level() <= self.min_level
+        let min_level = TEMPORARY_LEVEL.with(|level| match level.get() {
+            Some(level) => level.max(self.min_level()),
+            None => self.min_level(),
+        });
+        metadata.level() <= min_level
     }
 
     #[inline]
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
-            if let Some(filters) = &self.filters {
-                if !filters.apply(record.target(), record.level()) {
-                    self.print(record);
-                }
-            } else {
+            let hidden = match &*self.filters.load() {
+                Some(filters) => filters.apply_record(record),
+                None => false,
+            };
+            if !hidden {
                 self.print(record);
             }
         }
     }
 
-    #[inline(always)]
-    fn flush(&self) {}
+    #[inline]
+    fn flush(&self) {
+        let fmt = self.fmt.read().unwrap_or_else(|err| err.into_inner());
+        self.handle_error(fmt.flush());
+        for format in self.formats.values() {
+            if !std::sync::Arc::ptr_eq(format, &fmt) {
+                self.handle_error(format.flush())
+            }
+        }
+    }
 }
 
-/// Init a logger with a minimum level and no filters
-pub fn init<F: format::Print + 'static>(
-    format: F,
-    min_level: log::LevelFilter,
-) -> Result<(), log::SetLoggerError> {
-    let instance = INSTANCE.get_or_init(|| Logger {
-        fmt: Box::new(format),
-        min_level,
+/// A type that can be used as the minimum level
+///
+/// This is implemented for `log::LevelFilter`, `log::Level` and level names such as `"debug"`
+#[cfg(feature = "std")]
+pub trait IntoLevelFilter {
+    /// Convert this into a `log::LevelFilter`
+    fn into_level_filter(self) -> Result<log::LevelFilter, filtered::ParseLevelError>;
+}
+
+#[cfg(feature = "std")]
+impl IntoLevelFilter for log::LevelFilter {
+    fn into_level_filter(self) -> Result<log::LevelFilter, filtered::ParseLevelError> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoLevelFilter for log::Level {
+    fn into_level_filter(self) -> Result<log::LevelFilter, filtered::ParseLevelError> {
+        Ok(self.to_level_filter())
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoLevelFilter for &str {
+    fn into_level_filter(self) -> Result<log::LevelFilter, filtered::ParseLevelError> {
+        filtered::parse_level(self).ok_or_else(|| filtered::ParseLevelError(self.to_string()))
+    }
+}
+
+/// An error returned when the logger could not be initialized
+#[derive(Debug)]
+#[cfg(feature = "std")]
+pub enum InitError {
+    /// The minimum level could not be parsed
+    Level(filtered::ParseLevelError),
+    /// A logger has already been set
+    Logger(log::SetLoggerError),
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Level(err) => err.fmt(f),
+            Self::Logger(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Level(err) => Some(err),
+            Self::Logger(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<filtered::ParseLevelError> for InitError {
+    fn from(err: filtered::ParseLevelError) -> Self {
+        Self::Level(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<log::SetLoggerError> for InitError {
+    fn from(err: log::SetLoggerError) -> Self {
+        Self::Logger(err)
+    }
+}
+
+/// What to do when printing a record fails
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum OnError {
+    /// Ignore the error
+    Ignore,
+    /// Write a warning to stderr, only for the first error
+    Stderr,
+    /// Panic with the error
+    Panic,
+}
+
+/// Defaults to `Ignore`
+impl Default for OnError {
+    fn default() -> Self {
+        Self::Ignore
+    }
+}
+
+/// Configure the logger before installing it
+#[cfg(feature = "std")]
+pub struct Builder {
+    fmt: std::sync::Arc<dyn format::Print>,
+    formats: std::collections::HashMap<String, std::sync::Arc<dyn format::Print>>,
+    min_level: Result<log::LevelFilter, filtered::ParseLevelError>,
+    filters: Option<filtered::Filtered>,
+    on_error: OnError,
+}
+
+/// Get a builder for the logger, using this format
+///
+/// This defaults to a minimum level of `Trace`, no filters and ignoring errors
+#[cfg(feature = "std")]
+pub fn builder<F: format::Print + 'static>(format: F) -> Builder {
+    Builder {
+        fmt: std::sync::Arc::new(format),
+        formats: Default::default(),
+        min_level: Ok(log::LevelFilter::Trace),
         filters: None,
-    });
+        on_error: OnError::default(),
+    }
+}
+
+#[cfg(feature = "std")]
+impl Builder {
+    /// Set the minimum level
+    ///
+    /// An invalid level name is reported when the logger is initialized
+    ///
+    /// The level is capped at compile time by `log::STATIC_MAX_LEVEL`. With the
+    /// `release_max_level_info` feature (mirroring `log`'s) a minimum level of `Trace` acts like
+    /// `Info` in release builds, and the `debug!` and `trace!` calls are compiled out. Any of
+    /// `log`'s other `max_level_*` features can be enabled on `log` directly for other caps.
+    pub fn with_min_level(mut self, min_level: impl IntoLevelFilter) -> Self {
+        self.min_level = min_level.into_level_filter();
+        self
+    }
+
+    pub fn with_filters(mut self, filters: filtered::Filtered) -> Self {
+        self.filters.replace(filters);
+        self
+    }
+
+    pub fn without_filters(mut self) -> Self {
+        self.filters.take();
+        self
+    }
+
+    /** Register another format by name, which can be switched to with `set_active_format`
+
+    The format passed to `builder` is registered as `"default"`, and is active until another
+    one is switched to. Registering a name again replaces the earlier format.
+    */
+    pub fn with_format<F: format::Print + 'static>(mut self, name: &str, format: F) -> Self {
+        self.formats
+            .insert(name.to_string(), std::sync::Arc::new(format));
+        self
+    }
 
-    log::set_max_level(log::LevelFilter::Trace);
-    log::set_logger(instance)
+    /// Set what to do when printing a record fails
+    pub fn with_error_policy(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Install the logger
+    pub fn init(self) -> Result<(), InitError> {
+        let logger = self.build_logger()?;
+        let instance = INSTANCE.get_or_init(|| logger);
+
+        update_max_level(instance);
+        log::set_logger(instance).map_err(Into::into)
+    }
+
+    /// Install the logger, ignoring any errors
+    pub fn try_init(self) {
+        let _ = self.init();
+    }
+
+    /// Build the logger without installing it
+    ///
+    /// This is useful in tests, records can be passed to `log::Log::log` directly
+    pub fn build(self) -> Result<impl log::Log, InitError> {
+        self.build_logger()
+    }
+
+    fn build_logger(self) -> Result<Logger, InitError> {
+        let (fmt, mut formats) = (self.fmt, self.formats);
+        formats
+            .entry(DEFAULT_FORMAT.to_string())
+            .or_insert_with(|| std::sync::Arc::clone(&fmt));
+        Ok(Logger {
+            fmt: std::sync::RwLock::new(fmt),
+            formats,
+            filters: std::sync::Arc::new(arc_swap::ArcSwapOption::from_pointee(self.filters)),
+            min_level: (self.min_level? as usize).into(),
+            on_error: self.on_error,
+            warned: Default::default(),
+        })
+    }
+}
+
+/// Init a logger with a minimum level and no filters
+#[cfg(feature = "std")]
+pub fn init<F, L>(format: F, min_level: L) -> Result<(), InitError>
+where
+    F: format::Print + 'static,
+    L: IntoLevelFilter,
+{
+    builder(format).with_min_level(min_level).init()
 }
 
 /// Init a logger with a minimum level and filters
-pub fn init_with_filters<F>(
+#[cfg(feature = "std")]
+pub fn init_with_filters<F, L>(
     format: F,
-    min_level: log::LevelFilter,
+    min_level: L,
     filters: filtered::Filtered,
-) -> Result<(), log::SetLoggerError>
+) -> Result<(), InitError>
 where
     F: format::Print + 'static,
+    L: IntoLevelFilter,
 {
-    let instance = INSTANCE.get_or_init(|| Logger {
-        fmt: Box::new(format),
-        min_level,
-        filters: filters.into(),
-    });
-
-    log::set_max_level(log::LevelFilter::Trace);
-    log::set_logger(instance)
+    builder(format)
+        .with_min_level(min_level)
+        .with_filters(filters)
+        .init()
 }
 
 /// Init a logger with a minimum level and filters, ignoring any errors
-pub fn try_init_with_filters<F>(format: F, min_level: log::LevelFilter, filters: filtered::Filtered)
+#[cfg(feature = "std")]
+pub fn try_init_with_filters<F, L>(format: F, min_level: L, filters: filtered::Filtered)
 where
     F: format::Print + 'static,
+    L: IntoLevelFilter,
 {
     let _ = init_with_filters(format, min_level, filters);
 }
 
 /// Init a logger with a minimum level and no filters, ignoring any errors
-pub fn try_init<F: format::Print + 'static>(format: F, min_level: log::LevelFilter) {
+#[cfg(feature = "std")]
+pub fn try_init<F, L>(format: F, min_level: L)
+where
+    F: format::Print + 'static,
+    L: IntoLevelFilter,
+{
     let _ = crate::init(format, min_level);
 }
 
+/** Install a `Pretty` logger with sensible defaults, ignoring any errors
+
+This applies:
+- the level, the target, the log site, the thread and the uptime, see `PrettyBuilder::verbose`
+- colors when stdout is a terminal, unless `RUST_LOG_STYLE` says otherwise
+- `Info` and up, unless `RUST_LOG` has a bare level like `RUST_LOG=debug`
+- the filters in `RUST_LOG`, see `Filtered::from_env`
+
+Nothing happens if a logger was already installed. Use `builder` for anything else.
+*/
+#[cfg(feature = "std")]
+pub fn quick_start() {
+    try_init_with_filters(
+        logger::Pretty::builder().verbose().build(),
+        log::LevelFilter::Trace,
+        quick_start_filters(filtered::Filtered::from_env()),
+    )
+}
+
+// the minimum level is `Trace` so `RUST_LOG` can show anything, the default level does the rest
+#[cfg(feature = "std")]
+fn quick_start_filters(rust_log: filtered::Filtered) -> filtered::Filtered {
+    let mut filters = filtered::Filtered::default().with_default(log::LevelFilter::Info);
+    filters.merge(rust_log);
+    filters
+}
+
+/// Get the minimum level of the installed logger
+///
+/// This returns `None` if a logger from this crate hasn't been installed
+#[cfg(feature = "std")]
+pub fn min_level() -> Option<log::LevelFilter> {
+    INSTANCE.get().map(Logger::min_level)
+}
+
+/// Change the minimum level of the installed logger
+///
+/// This is still capped by `log::STATIC_MAX_LEVEL`, and returns `false` if a logger from this
+/// crate hasn't been installed
+#[cfg(feature = "std")]
+pub fn set_min_level(min_level: impl IntoLevelFilter) -> Result<bool, filtered::ParseLevelError> {
+    let min_level = min_level.into_level_filter()?;
+    let logger = match INSTANCE.get() {
+        Some(logger) => logger,
+        None => return Ok(false),
+    };
+    logger.set_min_level(min_level);
+    update_max_level(logger);
+    Ok(true)
+}
+
+#[cfg(feature = "std")]
+thread_local!(static TEMPORARY_LEVEL: std::cell::Cell<Option<log::LevelFilter>> = const { std::cell::Cell::new(None) });
+
+/// How many `with_temporary_level` calls are running, on any thread
+#[cfg(feature = "std")]
+static TEMPORARY_SCOPES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+// while a temporary level is active the `log` macros can't skip the logger, it decides per thread
+#[cfg(feature = "std")]
+fn update_max_level(logger: &Logger) {
+    if TEMPORARY_SCOPES.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+        log::set_max_level(log::STATIC_MAX_LEVEL);
+    } else {
+        log::set_max_level(logger.min_level().min(log::STATIC_MAX_LEVEL));
+    }
+}
+
+/** Run `f` with the minimum level raised to `level`, on this thread
+
+The previous level is restored when `f` returns, or panics. Calls can be nested, and a level
+lower than the logger's minimum level has no effect. Filters still apply.
+
+While any thread is in `f`, `log`'s global max level is raised to `log::STATIC_MAX_LEVEL` so
+the records reach the logger, which makes disabled records on other threads a little more
+expensive. Records above `log::STATIC_MAX_LEVEL` are compiled out, e.g. `trace!` with the
+`release_max_level_info` feature in release builds, and can't be raised.
+
+This still runs `f` if a logger from this crate hasn't been installed.
+
+# Usage
+```rust
+# fn do_work() {}
+arbitrary_logger::with_temporary_level(log::LevelFilter::Trace, || do_work());
+```
+*/
+#[cfg(feature = "std")]
+pub fn with_temporary_level<R>(level: log::LevelFilter, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<log::LevelFilter>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            TEMPORARY_LEVEL.with(|level| level.set(self.0));
+            TEMPORARY_SCOPES.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            if let Some(logger) = INSTANCE.get() {
+                update_max_level(logger);
+            }
+        }
+    }
+
+    let previous = TEMPORARY_LEVEL
+        .with(|current| current.replace(Some(level.max(current.get().unwrap_or(level)))));
+    TEMPORARY_SCOPES.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    if let Some(logger) = INSTANCE.get() {
+        update_max_level(logger);
+    }
+
+    let _restore = Restore(previous);
+    f()
+}
+
+/// The name of the format passed to `builder`
+#[cfg(feature = "std")]
+pub const DEFAULT_FORMAT: &str = "default";
+
+/** Switch the installed logger to a format registered with `Builder::with_format`
+
+The swap is behind a lock: records being printed on other threads finish with the old format,
+and every record after this returns uses the new one. A record is never printed by two formats.
+
+This returns `false` if no format has this name, or if a logger from this crate hasn't been
+installed.
+*/
+#[cfg(feature = "std")]
+pub fn set_active_format(name: &str) -> bool {
+    INSTANCE
+        .get()
+        .map(|logger| logger.set_active_format(name))
+        .unwrap_or(false)
+}
+
+/// Replace the filters of the installed logger, `None` removes them
+///
+/// This returns `false` if a logger from this crate hasn't been installed
+#[cfg(feature = "std")]
+pub fn reload_filters(filters: impl Into<Option<filtered::Filtered>>) -> bool {
+    INSTANCE
+        .get()
+        .map(|logger| logger.set_filters(filters.into()))
+        .is_some()
+}
+
+/** Get a handle for replacing the filters of the installed logger
+
+The handle can be kept around, e.g. in a `SIGHUP` handler:
+
+```rust,no_run
+# use arbitrary_logger::{filtered::Filtered, logger::Pretty};
+arbitrary_logger::init_with_filters(Pretty::default(), "trace", Filtered::from_env()).unwrap();
+let handle = arbitrary_logger::reload_handle().unwrap();
+// later
+handle.reload(Filtered::from_str("hyper=debug"));
+```
+
+This returns `None` if a logger from this crate hasn't been installed
+*/
+#[cfg(feature = "std")]
+pub fn reload_handle() -> Option<ReloadHandle> {
+    INSTANCE.get().map(Logger::reload_handle)
+}
+
+/** A handle for replacing the filters of a logger, see [`reload_handle`](./fn.reload_handle.html)
+
+Replacing the filters is atomic, every record uses either the old or the new filters, and
+reading them doesn't take a lock.
+*/
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct ReloadHandle {
+    filters: std::sync::Arc<arc_swap::ArcSwapOption<filtered::Filtered>>,
+}
+
+#[cfg(feature = "std")]
+impl ReloadHandle {
+    /// Replace the filters, `None` removes them
+    pub fn reload(&self, filters: impl Into<Option<filtered::Filtered>>) {
+        self.filters.store(filters.into().map(std::sync::Arc::new))
+    }
+}
+
+/** Change the filters of the installed logger in place
+
+This is useful for raising the level of a single target while the program runs:
+
+```rust,no_run
+arbitrary_logger::update_filters(|filters| filters.insert("mycrate::db", log::LevelFilter::Off));
+```
+
+If the logger has no filters, this starts from an empty set. The update is made to a copy of
+the filters, which then replaces them, so it can run more than once if the filters are
+changed at the same time. This returns `false` if a logger from this crate hasn't been
+installed.
+*/
+#[cfg(feature = "std")]
+pub fn update_filters(update: impl FnMut(&mut filtered::Filtered)) -> bool {
+    INSTANCE
+        .get()
+        .map(|logger| logger.update_filters(update))
+        .is_some()
+}
+
+/// A function providing extra key-values for every record
+#[cfg(feature = "kv")]
+pub type ContextProvider = dyn Fn() -> Vec<(&'static str, String)> + Send + Sync;
+
+#[cfg(feature = "kv")]
+static CONTEXT: OnceCell<Box<ContextProvider>> = OnceCell::new();
+
+/** Set a context provider, whose pairs are added to the key-values of every record
+
+This is called for each record that is going to be printed, on the thread that logged it. So
+it can read thread-locals, e.g. a current `trace_id` and `span_id`.
+
+Only one provider can be set, this returns `false` if one was already set.
+
+# Usage
+```rust
+# use arbitrary_logger::set_context_provider;
+thread_local!(static TRACE_ID: std::cell::Cell<u64> = const { std::cell::Cell::new(0) });
+
+set_context_provider(|| vec![("trace_id", TRACE_ID.with(|id| id.get()).to_string())]);
+```
+*/
+#[cfg(feature = "kv")]
+pub fn set_context_provider<F>(provider: F) -> bool
+where
+    F: Fn() -> Vec<(&'static str, String)> + Send + Sync + 'static,
+{
+    CONTEXT.set(Box::new(provider)).is_ok()
+}
+
+#[cfg(feature = "kv")]
+struct Context<'a> {
+    record: &'a dyn log::kv::Source,
+    pairs: &'a [(&'static str, String)],
+}
+
+#[cfg(feature = "kv")]
+impl log::kv::Source for Context<'_> {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn log::kv::VisitSource<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        self.record.visit(visitor)?;
+        for (key, value) in self.pairs {
+            visitor.visit_pair(log::kv::Key::from_str(key), value.as_str().into())?;
+        }
+        Ok(())
+    }
+}
+
 /// Different continuation string: `⤷`
 pub static DEFAULT_CONTINUATION: &str = "⤷";
 
-#[cfg(test)]
+#[cfg(feature = "std")]
+static CONTINUATION: OnceCell<&'static str> = OnceCell::new();
+
+/// Set the continuation used by `with_continuation(None)`, instead of `DEFAULT_CONTINUATION`
+///
+/// This can only be set once, it returns `false` if it was already set
+#[cfg(feature = "std")]
+pub fn set_default_continuation(cont: &'static str) -> bool {
+    CONTINUATION.set(cont).is_ok()
+}
+
+/// Get the continuation used by `with_continuation(None)`
+#[cfg(feature = "std")]
+pub fn default_continuation() -> &'static str {
+    CONTINUATION.get().copied().unwrap_or(DEFAULT_CONTINUATION)
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type FormatFn = dyn Fn(&log::Record) -> String + Send + Sync;
+
+    /// Collects a line for each printed record, for the tests of the loggers that wrap others
+    pub(crate) struct Capture {
+        lines: Arc<Mutex<Vec<String>>>,
+        format: Box<FormatFn>,
+    }
+
+    impl Capture {
+        /// Collect the message of each record
+        pub(crate) fn new(lines: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self::with_format(lines, |record| record.args().to_string())
+        }
+
+        /// Collect the target and the message of each record, separated by a space
+        pub(crate) fn with_target(lines: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self::with_format(lines, |record| {
+                format!("{} {}", record.target(), record.args())
+            })
+        }
+
+        /// Collect each record formatted with `format`
+        pub(crate) fn with_format<F>(lines: &Arc<Mutex<Vec<String>>>, format: F) -> Self
+        where
+            F: Fn(&log::Record) -> String + Send + Sync + 'static,
+        {
+            Self {
+                lines: Arc::clone(lines),
+                format: Box::new(format),
+            }
+        }
+    }
+
+    impl format::Print for Capture {
+        fn print(&self, record: &log::Record) -> std::io::Result<()> {
+            let line = (self.format)(record);
+            self.lines.lock().unwrap().push(line);
+            Ok(())
+        }
+    }
+
+    /// Renders just the message of a record, for the tests of the loggers that take a `Render`
+    pub(crate) struct Message;
+
+    impl format::Render for Message {
+        fn render(&self, record: &log::Record, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+            writeln!(w, "{}", record.args())
+        }
+    }
 
     #[test]
     fn new_filtered() {
-        let filtered = filtered::Filtered::new(&["tokio::io=trace", "mio=debug"]);
+        let filtered = filtered::Filtered::new(["tokio::io=trace", "mio=debug"]);
 
         for (ignored, level) in &[
             ("mio::uds::spam", log::Level::Trace),
@@ -123,6 +744,290 @@ mod tests {
         }
     }
 
+    #[test]
+    fn level_from_str() {
+        assert_eq!("debug".into_level_filter(), Ok(log::LevelFilter::Debug));
+        assert_eq!("WARN".into_level_filter(), Ok(log::LevelFilter::Warn));
+        assert_eq!(
+            log::Level::Info.into_level_filter(),
+            Ok(log::LevelFilter::Info)
+        );
+
+        assert!("verbose".into_level_filter().is_err());
+        assert!(matches!(
+            crate::init(crate::logger::Pretty::default(), "verbose"),
+            Err(InitError::Level(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn context_provider() {
+        struct Visit<'a>(&'a mut String);
+        impl<'kvs> log::kv::VisitSource<'kvs> for Visit<'_> {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push_str(&format!(" {}={}", key, value));
+                Ok(())
+            }
+        }
+
+        thread_local!(static TRACE_ID: std::cell::Cell<u64> = const { std::cell::Cell::new(0) });
+        assert!(crate::set_context_provider(|| vec![
+            ("trace_id", TRACE_ID.with(|id| id.get()).to_string()),
+            ("span_id", "a1".to_string()),
+        ]));
+
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let capture = Capture::with_format(&lines, |record| {
+            let mut line = record.args().to_string();
+            record.key_values().visit(&mut Visit(&mut line)).unwrap();
+            line
+        });
+        let logger = crate::builder(capture).build_logger().unwrap();
+
+        TRACE_ID.with(|id| id.set(42));
+        log::Log::log(
+            &logger,
+            &log::Record::builder()
+                .args(format_args!("hello"))
+                .key_values(&[("user", "bob")])
+                .build(),
+        );
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["hello user=bob trace_id=42 span_id=a1"]
+        );
+    }
+
+    #[test]
+    fn error_policy() {
+        struct Broken;
+        impl format::Print for Broken {
+            fn print(&self, _: &log::Record) -> std::io::Result<()> {
+                Err(std::io::Error::other("read-only"))
+            }
+        }
+
+        let log = |on_error| {
+            let logger = crate::builder(Broken)
+                .with_error_policy(on_error)
+                .build_logger()
+                .unwrap();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                log::Log::log(
+                    &logger,
+                    &log::Record::builder().args(format_args!("hello")).build(),
+                )
+            }));
+            (result.is_ok(), logger.warned.into_inner())
+        };
+
+        assert_eq!(log(OnError::Ignore), (true, false));
+        assert_eq!(log(OnError::Stderr), (true, true));
+        assert_eq!(log(OnError::Panic), (false, false));
+    }
+
+    #[test]
+    fn build() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let logger = crate::logger::build(
+            Capture::with_target(&lines),
+            "info",
+            filtered::Filtered::new(["foo::hidden=info"]),
+        )
+        .unwrap();
+
+        for (target, level) in &[
+            ("foo", log::Level::Info),
+            ("foo", log::Level::Debug),
+            ("foo::hidden", log::Level::Info),
+            ("foo::hidden", log::Level::Warn),
+        ] {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .args(format_args!("{}", level))
+                    .level(*level)
+                    .target(target)
+                    .build(),
+            );
+        }
+
+        assert_eq!(*lines.lock().unwrap(), vec!["foo INFO", "foo::hidden WARN"]);
+        assert!(crate::logger::build(Capture::with_target(&lines), "verbose", None).is_err());
+    }
+
+    #[test]
+    fn set_min_level() {
+        let logger = crate::builder(crate::logger::Tsv::new())
+            .with_min_level("info")
+            .build_logger()
+            .unwrap();
+        assert_eq!(logger.min_level(), log::LevelFilter::Info);
+        for level in log::LevelFilter::iter() {
+            logger.set_min_level(level);
+            assert_eq!(logger.min_level(), level);
+        }
+    }
+
+    #[test]
+    fn set_filters() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let logger = crate::builder(Capture::new(&lines)).build_logger().unwrap();
+        let log = |msg: &str| {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Debug)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        log("before");
+        logger.set_filters(Some(filtered::Filtered::new(["foo=debug"])));
+        log("hidden");
+        logger.set_filters(None);
+        log("after");
+        logger.update_filters(|filters| filters.insert("foo", log::LevelFilter::Debug));
+        log("hidden");
+        logger.update_filters(|filters| {
+            filters.remove("foo");
+        });
+        log("removed");
+
+        assert_eq!(*lines.lock().unwrap(), vec!["before", "after", "removed"]);
+    }
+
+    #[test]
+    fn reload_handle() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let logger = crate::builder(Capture::new(&lines))
+            .with_filters(filtered::Filtered::new(["foo=trace"]))
+            .build_logger()
+            .unwrap();
+        let log = |msg: &str| {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let handle = logger.reload_handle();
+        log("before");
+        handle.clone().reload(filtered::Filtered::new(["foo=info"]));
+        log("hidden");
+        handle.reload(None);
+        log("after");
+
+        assert_eq!(*lines.lock().unwrap(), vec!["before", "after"]);
+    }
+
+    #[test]
+    fn temporary_level() {
+        let logger = crate::builder(crate::logger::Tsv::new())
+            .with_min_level("info")
+            .build_logger()
+            .unwrap();
+        let enabled =
+            |level| log::Log::enabled(&logger, &log::Metadata::builder().level(level).build());
+
+        assert!(!enabled(log::Level::Debug));
+        crate::with_temporary_level(log::LevelFilter::Debug, || {
+            assert!(enabled(log::Level::Debug));
+            assert!(!enabled(log::Level::Trace));
+
+            crate::with_temporary_level(log::LevelFilter::Trace, || {
+                assert!(enabled(log::Level::Trace))
+            });
+            crate::with_temporary_level(log::LevelFilter::Error, || {
+                assert!(enabled(log::Level::Debug))
+            });
+            assert!(!enabled(log::Level::Trace));
+
+            std::thread::scope(|s| {
+                s.spawn(|| assert!(!enabled(log::Level::Debug)));
+            });
+        });
+        assert!(!enabled(log::Level::Debug));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            crate::with_temporary_level(log::LevelFilter::Trace, || panic!("boom"))
+        }));
+        assert!(result.is_err());
+        assert!(!enabled(log::Level::Debug));
+    }
+
+    #[test]
+    fn active_format() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let capture = |name: &'static str| {
+            Capture::with_format(&lines, move |record| format!("{}: {}", name, record.args()))
+        };
+        let logger = crate::builder(capture("text"))
+            .with_format("json", capture("json"))
+            .build_logger()
+            .unwrap();
+        let log = |msg: &str| {
+            log::Log::log(
+                &logger,
+                &log::Record::builder().args(format_args!("{}", msg)).build(),
+            )
+        };
+
+        log("a");
+        assert!(logger.set_active_format("json"));
+        log("b");
+        assert!(!logger.set_active_format("yaml"));
+        log("c");
+        assert!(logger.set_active_format(DEFAULT_FORMAT));
+        log("d");
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["text: a", "json: b", "json: c", "text: d"]
+        );
+    }
+
+    #[test]
+    fn quick_start_filters() {
+        let filters = |rust_log| crate::quick_start_filters(filtered::Filtered::from_str(rust_log));
+
+        let quick_start = filters("");
+        assert_eq!(quick_start.level_for("foo"), log::LevelFilter::Info);
+        assert_eq!(quick_start.filtered().count(), 0);
+
+        assert_eq!(filters("debug").level_for("foo"), log::LevelFilter::Debug);
+
+        let quick_start = filters("mio=info,hyper=off");
+        assert_eq!(quick_start.level_for("foo"), log::LevelFilter::Info);
+        assert_eq!(quick_start.level_for("mio"), log::LevelFilter::Warn);
+        assert_eq!(quick_start.level_for("hyper"), log::LevelFilter::Off);
+
+        for rust_log in &["debug,hyper=warn", "hyper=warn,debug"] {
+            let quick_start = filters(rust_log);
+            assert_eq!(quick_start.level_for("mio"), log::LevelFilter::Debug);
+            assert_eq!(quick_start.level_for("hyper"), log::LevelFilter::Error);
+        }
+    }
+
+    #[test]
+    fn default_continuation() {
+        assert!(crate::set_default_continuation("->"));
+        assert!(!crate::set_default_continuation("=>"));
+        assert_eq!(crate::default_continuation(), "->");
+    }
+
     #[test]
     fn new_rust_log() {
         std::env::set_var("RUST_LOG", "tokio=trace, ")
@@ -154,7 +1059,7 @@ mod tests {
         crate::try_init_with_filters(
             crate::logger::Pretty::default(),
             log::LevelFilter::Trace,
-            filtered::Filtered::new(&[
+            filtered::Filtered::new([
                 "arbitrary_logger::tests::hidden=warn",
                 "arbitrary_logger::tests::nested::hidden=info",
             ]),
@@ -0,0 +1,173 @@
+//! A Common Log Format logger
+//!
+use crate::format::{Print, Render, Utc};
+
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+/** A logger writing access logs in the Common Log Format, like Apache's
+
+Each record is a line of `host ident authuser [date] "request" status bytes`, e.g.
+```text
+127.0.0.1 - frank [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif HTTP/1.0" 200 2326
+```
+
+The fields are read from the record's key-values, a missing one is written as `-`:
+
+| key       | field      | when missing |
+| --------- | ---------- | ------------ |
+| `host`    | `host`     | `-`          |
+| `ident`   | `ident`    | `-`          |
+| `user`    | `authuser` | `-`          |
+| `request` | `request`  | the message  |
+| `status`  | `status`   | `-`          |
+| `bytes`   | `bytes`    | `-`          |
+
+The date is the time the record is written, in UTC. The level and target aren't written, so
+access logs are usually sent to their own target and filtered to this logger.
+
+This needs the `kv` feature.
+
+# Usage
+```rust
+# use arbitrary_logger::logger::AccessLog;
+arbitrary_logger::try_init(AccessLog::new(), log::LevelFilter::Info);
+log::info!(host = "127.0.0.1", status = 200, bytes = 2326; "GET /index.html HTTP/1.1");
+```
+*/
+#[derive(Default)]
+pub struct AccessLog {
+    _private: (),
+}
+
+impl AccessLog {
+    /// Create a Common Log Format logger
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn render_at(
+        &self,
+        record: &log::Record,
+        w: &mut dyn Write,
+        now: SystemTime,
+    ) -> std::io::Result<()> {
+        let kvs = record.key_values();
+        let field = |key: &str| kvs.get(log::kv::Key::from_str(key));
+        let write_field = |w: &mut dyn Write, key: &str| match field(key) {
+            Some(value) => write!(w, "{}", value),
+            None => write!(w, "-"),
+        };
+
+        write_field(w, "host")?;
+        write!(w, " ")?;
+        write_field(w, "ident")?;
+        write!(w, " ")?;
+        write_field(w, "user")?;
+
+        let secs = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        write!(w, " [")?;
+        Utc::format_common_log(w, secs)?;
+        write!(w, "] \"")?;
+        match field("request") {
+            Some(request) => write!(Quoted(w), "{}", request)?,
+            None => write!(Quoted(w), "{}", record.args())?,
+        }
+        write!(w, "\" ")?;
+
+        write_field(w, "status")?;
+        write!(w, " ")?;
+        write_field(w, "bytes")?;
+        writeln!(w)
+    }
+}
+
+impl Render for AccessLog {
+    fn render(&self, record: &log::Record, w: &mut dyn Write) -> std::io::Result<()> {
+        self.render_at(record, w, SystemTime::now())
+    }
+}
+
+impl Print for AccessLog {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        self.render(record, &mut buffer)?;
+        std::io::stdout().lock().write_all(&buffer)
+    }
+}
+
+// escapes quotes, backslashes and control characters inside the quoted request, like Apache
+struct Quoted<'a>(&'a mut dyn Write);
+
+impl Write for Quoted<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let escaped = |&b: &u8| b < 0x20 || b == b'"' || b == b'\\' || b == 0x7f;
+        for chunk in buf.split_inclusive(escaped) {
+            match chunk.split_last() {
+                Some((&last, head)) if escaped(&last) => {
+                    self.0.write_all(head)?;
+                    match last {
+                        b'"' | b'\\' => write!(self.0, "\\{}", last as char)?,
+                        b => write!(self.0, "\\x{:02x}", b)?,
+                    }
+                }
+                _ => self.0.write_all(chunk)?,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(msg: &str, kvs: &[(&str, log::kv::Value)]) -> String {
+        let mut buffer = vec![];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(971_186_136);
+        AccessLog::new()
+            .render_at(
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .key_values(&kvs)
+                    .build(),
+                &mut buffer,
+                now,
+            )
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn common_log_format() {
+        assert_eq!(
+            render(
+                "ignored",
+                &[
+                    ("host", "127.0.0.1".into()),
+                    ("ident", "-".into()),
+                    ("user", "frank".into()),
+                    ("request", "GET /apache_pb.gif HTTP/1.0".into()),
+                    ("status", 200.into()),
+                    ("bytes", 2326.into()),
+                ]
+            ),
+            "127.0.0.1 - frank [10/Oct/2000:13:55:36 +0000] \"GET /apache_pb.gif HTTP/1.0\" 200 2326\n"
+        );
+    }
+
+    #[test]
+    fn missing_fields() {
+        assert_eq!(
+            render("GET /a\"b HTTP/1.1", &[("status", 404.into())]),
+            "- - - [10/Oct/2000:13:55:36 +0000] \"GET /a\\\"b HTTP/1.1\" 404 -\n"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! A buffering logger
+//!
+use crate::format::{Print, Render};
+
+use std::io::Write;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How many bytes are buffered by default before writing
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/** A logger that renders records into a buffer and writes them out in batches
+
+This trades latency for throughput: a file or socket gets one large write instead of one per
+record. The buffer is written out when:
+- it reaches its capacity (8 KiB by default, see `with_capacity`)
+- the `interval` passes, checked by a background thread
+- `log::logger().flush()` is called
+- the logger is dropped
+
+A logger installed with `init` is never dropped, so call `log::logger().flush()` before exiting
+or the last records (up to one `interval` worth) are lost.
+
+# Usage
+```rust
+# use arbitrary_logger::logger::{Buffered, Tsv};
+# use std::time::Duration;
+let logger = Buffered::new(Tsv::new(), std::io::stderr(), Duration::from_millis(100));
+arbitrary_logger::try_init(logger, log::LevelFilter::Trace);
+// ...
+log::logger().flush();
+```
+*/
+pub struct Buffered<R, W: Write> {
+    render: R,
+    capacity: usize,
+    shared: Arc<Mutex<Shared<W>>>,
+    _stop: Sender<()>,
+}
+
+struct Shared<W: Write> {
+    buffer: Vec<u8>,
+    writer: W,
+}
+
+impl<W: Write> Shared<W> {
+    fn write_out(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let result = self.writer.write_all(&self.buffer);
+        self.buffer.clear();
+        result?;
+        self.writer.flush()
+    }
+}
+
+impl<R, W> Buffered<R, W>
+where
+    R: Render,
+    W: Write + Send + 'static,
+{
+    /// Render records with `render`, writing them to `writer` at least every `interval`
+    pub fn new(render: R, writer: W, interval: Duration) -> Self {
+        let shared = Arc::new(Mutex::new(Shared {
+            buffer: Vec::with_capacity(DEFAULT_CAPACITY),
+            writer,
+        }));
+
+        let (stop, stopped) = std::sync::mpsc::channel();
+        let timer = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("arbitrary_logger".into())
+            .spawn(move || run_timer(timer, stopped, interval))
+            .expect("spawn buffered timer");
+
+        Self {
+            render,
+            capacity: DEFAULT_CAPACITY,
+            shared,
+            _stop: stop,
+        }
+    }
+
+    /// Write the buffer out once it reaches `capacity` bytes
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+fn run_timer<W: Write>(shared: Arc<Mutex<Shared<W>>>, stopped: Receiver<()>, interval: Duration) {
+    // the sender is never used, this stops once the logger is dropped
+    while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+        let mut shared = shared.lock().unwrap_or_else(|err| err.into_inner());
+        let _ = shared.write_out();
+    }
+}
+
+impl<R, W> Print for Buffered<R, W>
+where
+    R: Render,
+    W: Write + Send,
+{
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut shared = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        self.render.render(record, &mut shared.buffer)?;
+        if shared.buffer.len() >= self.capacity {
+            return shared.write_out();
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        let mut shared = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        shared.write_out()
+    }
+}
+
+impl<R, W: Write> Drop for Buffered<R, W> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        let _ = shared.write_out();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Message;
+
+    #[derive(Clone, Default)]
+    struct Capture {
+        bytes: Arc<Mutex<Vec<u8>>>,
+        writes: Arc<Mutex<usize>>,
+    }
+
+    impl Capture {
+        fn lines(&self) -> Vec<String> {
+            let bytes = self.bytes.lock().unwrap();
+            std::str::from_utf8(&bytes)
+                .unwrap()
+                .lines()
+                .map(ToString::to_string)
+                .collect()
+        }
+    }
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            *self.writes.lock().unwrap() += 1;
+            self.bytes.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn print(logger: &impl Print, msg: &str) {
+        logger
+            .print(&log::Record::builder().args(format_args!("{}", msg)).build())
+            .unwrap()
+    }
+
+    #[test]
+    fn capacity_and_flush() {
+        let capture = Capture::default();
+        let logger =
+            Buffered::new(Message, capture.clone(), Duration::from_secs(60)).with_capacity(8);
+
+        print(&logger, "abc");
+        assert!(capture.lines().is_empty());
+        print(&logger, "defg"); // 9 bytes
+        assert_eq!(capture.lines(), vec!["abc", "defg"]);
+        assert_eq!(*capture.writes.lock().unwrap(), 1);
+
+        print(&logger, "h");
+        assert_eq!(capture.lines().len(), 2);
+        logger.flush().unwrap();
+        assert_eq!(capture.lines(), vec!["abc", "defg", "h"]);
+
+        print(&logger, "i");
+        drop(logger);
+        assert_eq!(capture.lines(), vec!["abc", "defg", "h", "i"]);
+    }
+
+    #[test]
+    fn interval() {
+        let capture = Capture::default();
+        let logger = Buffered::new(Message, capture.clone(), Duration::from_millis(50));
+
+        print(&logger, "hello");
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while capture.lines().is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(capture.lines(), vec!["hello"]);
+    }
+}
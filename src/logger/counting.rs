@@ -0,0 +1,169 @@
+//! A counting logger
+//!
+use crate::format::Print;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Records counted per level, from a [`Counting`](./struct.Counting.html) logger
+#[derive(Default)]
+pub struct Counters {
+    levels: [AtomicU64; 5],
+}
+
+impl Counters {
+    /// A snapshot of the counts
+    pub fn counts(&self) -> LevelCounts {
+        let count = |level: log::Level| self.levels[level as usize - 1].load(Ordering::Relaxed);
+        LevelCounts {
+            error: count(log::Level::Error),
+            warn: count(log::Level::Warn),
+            info: count(log::Level::Info),
+            debug: count(log::Level::Debug),
+            trace: count(log::Level::Trace),
+        }
+    }
+
+    fn increment(&self, level: log::Level) {
+        self.levels[level as usize - 1].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// How many records were printed at each level
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct LevelCounts {
+    pub error: u64,
+    pub warn: u64,
+    pub info: u64,
+    pub debug: u64,
+    pub trace: u64,
+}
+
+impl LevelCounts {
+    /// The count for this level
+    pub fn get(&self, level: log::Level) -> u64 {
+        match level {
+            log::Level::Error => self.error,
+            log::Level::Warn => self.warn,
+            log::Level::Info => self.info,
+            log::Level::Debug => self.debug,
+            log::Level::Trace => self.trace,
+        }
+    }
+
+    /// The count for every level
+    pub fn total(&self) -> u64 {
+        self.error + self.warn + self.info + self.debug + self.trace
+    }
+}
+
+/** A logger counting the records it prints, per level
+
+This is cheap enough to leave on, e.g. to export `log_messages_total{level="error"}` to a
+metrics system. The counts reflect emitted records: anything below the minimum level or hidden
+by the filters never reaches this logger, so it isn't counted. A record is counted even if
+printing it fails.
+
+# Usage
+```rust
+# use arbitrary_logger::logger::{Counting, Tsv};
+let logger = Counting::new(Tsv::new());
+let counters = logger.counters();
+arbitrary_logger::try_init(logger, log::LevelFilter::Info);
+
+log::error!("boom");
+assert_eq!(counters.counts().error, 1);
+```
+*/
+pub struct Counting<P> {
+    inner: P,
+    counters: Arc<Counters>,
+}
+
+impl<P: Print> Counting<P> {
+    /// Wrap a logger, counting the records passed to it
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            counters: Arc::default(),
+        }
+    }
+
+    /// Get a handle to the counters, which can be kept after installing the logger
+    pub fn counters(&self) -> Arc<Counters> {
+        Arc::clone(&self.counters)
+    }
+
+    /// A snapshot of the counts
+    pub fn counts(&self) -> LevelCounts {
+        self.counters.counts()
+    }
+}
+
+impl<P: Print> Print for Counting<P> {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        self.counters.increment(record.level());
+        self.inner.print(record)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Discard;
+    impl Print for Discard {
+        fn print(&self, _: &log::Record) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn counts() {
+        let counting = Counting::new(Discard);
+        let counters = counting.counters();
+        let logger = crate::logger::build(
+            counting,
+            "debug",
+            crate::filtered::Filtered::new(["noisy=info"]),
+        )
+        .unwrap();
+
+        for (target, level) in &[
+            ("app", log::Level::Error),
+            ("app", log::Level::Warn),
+            ("app", log::Level::Warn),
+            ("app", log::Level::Debug),
+            ("app", log::Level::Trace),
+            ("noisy", log::Level::Info),
+            ("noisy", log::Level::Warn),
+        ] {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(*level)
+                    .target(target)
+                    .build(),
+            );
+        }
+
+        let counts = counters.counts();
+        assert_eq!(
+            counts,
+            LevelCounts {
+                error: 1,
+                warn: 3,
+                info: 0,
+                debug: 1,
+                trace: 0,
+            }
+        );
+        assert_eq!(counts.get(log::Level::Warn), 3);
+        assert_eq!(counts.total(), 5);
+    }
+}
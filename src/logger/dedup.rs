@@ -0,0 +1,260 @@
+//! A deduplicating logger
+//!
+use crate::format::Print;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/** A logger that collapses repeated messages
+
+Records are keyed on a hash of their log site, the `file()` and `line()`, so a site logging
+in a loop is printed once per `window` even when its arguments differ. Records without a
+location fall back to a hash of their formatted message (ignoring the level and target), which
+formats the message an extra time. A different key can be used with `with_key`, e.g.
+`with_message_key` to only collapse identical messages. Two records with the same key are
+treated as duplicates.
+
+The window starts when a message is first printed. Once it has passed, the next record (of any
+message) prints a summary for each suppressed message, like `last message repeated 3 times`,
+using the level and target of the first occurrence. A message is printed again the next time
+it shows up after its window.
+
+`flush` prints the summaries of every suppressed message without waiting for their windows, so
+nothing is lost at shutdown. Those messages are printed again the next time they show up.
+*/
+pub struct Dedup<P> {
+    inner: P,
+    window: Duration,
+    key: Box<KeyFn>,
+    seen: Mutex<HashMap<u64, Seen>>,
+}
+
+type KeyFn = dyn Fn(&log::Record) -> u64 + Send + Sync;
+
+struct Seen {
+    since: Instant,
+    suppressed: u64,
+    level: log::Level,
+    target: String,
+}
+
+impl<P: Print> Dedup<P> {
+    /// Wrap a logger, suppressing repeated messages for `window`
+    pub fn new(inner: P, window: Duration) -> Self {
+        Self {
+            inner,
+            window,
+            key: Box::new(location_key),
+            seen: Mutex::default(),
+        }
+    }
+
+    /// Key records with this function, records with the same key are duplicates
+    pub fn with_key<F>(mut self, key: F) -> Self
+    where
+        F: Fn(&log::Record) -> u64 + Send + Sync + 'static,
+    {
+        self.key = Box::new(key);
+        self
+    }
+
+    /// Key records on their formatted message, so only identical messages are duplicates
+    pub fn with_message_key(self) -> Self {
+        self.with_key(message_key)
+    }
+
+    fn print_summary(&self, seen: &Seen) -> std::io::Result<()> {
+        if seen.suppressed == 0 {
+            return Ok(());
+        }
+        self.inner.print(
+            &log::Record::builder()
+                .args(format_args!(
+                    "last message repeated {} times",
+                    seen.suppressed
+                ))
+                .level(seen.level)
+                .target(&seen.target)
+                .build(),
+        )
+    }
+}
+
+fn location_key(record: &log::Record) -> u64 {
+    match (record.file(), record.line()) {
+        (Some(file), Some(line)) => {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            (file, line).hash(&mut hasher);
+            hasher.finish()
+        }
+        _ => message_key(record),
+    }
+}
+
+fn message_key(record: &log::Record) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    record.args().to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<P: Print> Print for Dedup<P> {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let key = (self.key)(record);
+
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap_or_else(|err| err.into_inner());
+
+        let mut expired = seen
+            .iter()
+            .filter(|(_, seen)| now.duration_since(seen.since) >= self.window)
+            .map(|(&key, _)| key)
+            .collect::<Vec<_>>();
+        expired.sort_by_key(|key| seen[key].since);
+
+        for key in expired {
+            let seen = seen.remove(&key).expect("expired key");
+            self.print_summary(&seen)?;
+        }
+
+        if let Some(seen) = seen.get_mut(&key) {
+            seen.suppressed += 1;
+            return Ok(());
+        }
+
+        seen.insert(
+            key,
+            Seen {
+                since: now,
+                suppressed: 0,
+                level: record.level(),
+                target: record.target().to_string(),
+            },
+        );
+        drop(seen);
+
+        self.inner.print(record)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        let mut seen = self.seen.lock().unwrap_or_else(|err| err.into_inner());
+
+        let mut suppressed = seen
+            .iter()
+            .filter(|(_, seen)| seen.suppressed > 0)
+            .map(|(&key, _)| key)
+            .collect::<Vec<_>>();
+        suppressed.sort_by_key(|key| seen[key].since);
+
+        for key in suppressed {
+            let seen = seen.remove(&key).expect("suppressed key");
+            self.print_summary(&seen)?;
+        }
+        drop(seen);
+
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Capture;
+    use std::sync::Arc;
+
+    fn print(logger: &impl Print, target: &str, msg: &str) {
+        logger
+            .print(
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .target(target)
+                    .build(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn across_targets() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let dedup = Dedup::new(Capture::with_target(&lines), Duration::from_secs(60));
+
+        print(&dedup, "a", "boom");
+        print(&dedup, "b", "boom");
+        print(&dedup, "a", "other");
+        print(&dedup, "c", "boom");
+
+        assert_eq!(*lines.lock().unwrap(), vec!["a boom", "a other"]);
+    }
+
+    #[test]
+    fn log_site() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let dedup = Dedup::new(Capture::with_target(&lines), Duration::from_secs(60));
+        let at = |line: u32, msg: &str| {
+            dedup
+                .print(
+                    &log::Record::builder()
+                        .args(format_args!("{}", msg))
+                        .target("a")
+                        .file(Some("main.rs"))
+                        .line(Some(line))
+                        .build(),
+                )
+                .unwrap()
+        };
+
+        at(10, "got 1");
+        at(10, "got 2");
+        at(11, "got 1");
+        assert_eq!(*lines.lock().unwrap(), vec!["a got 1", "a got 1"]);
+
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let dedup = Dedup::new(Capture::with_target(&lines), Duration::from_secs(60))
+            .with_key(|record| record.level() as u64);
+        print(&dedup, "a", "one");
+        print(&dedup, "b", "two");
+        assert_eq!(*lines.lock().unwrap(), vec!["a one"]);
+    }
+
+    #[test]
+    fn summary_after_window() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let dedup = Dedup::new(Capture::with_target(&lines), Duration::from_millis(200));
+
+        print(&dedup, "a", "boom");
+        print(&dedup, "b", "boom");
+        print(&dedup, "b", "boom");
+        std::thread::sleep(Duration::from_millis(300));
+        print(&dedup, "a", "boom");
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["a boom", "a last message repeated 2 times", "a boom"]
+        );
+    }
+
+    #[test]
+    fn flush_summaries() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let dedup = Dedup::new(Capture::with_target(&lines), Duration::from_secs(60));
+
+        print(&dedup, "a", "boom");
+        print(&dedup, "a", "boom");
+        print(&dedup, "a", "boom");
+        print(&dedup, "b", "other");
+        dedup.flush().unwrap();
+        print(&dedup, "a", "boom");
+        print(&dedup, "b", "other");
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec![
+                "a boom",
+                "b other",
+                "a last message repeated 2 times",
+                "a boom"
+            ]
+        );
+    }
+}
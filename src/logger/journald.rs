@@ -0,0 +1,170 @@
+//! A systemd journal logger
+//!
+use crate::format::Print;
+
+use std::io::Write;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+
+/// Where journald listens for the native protocol
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/** A logger that sends records to the systemd journal, with native fields
+
+Each record is sent as a datagram using journald's
+[native protocol](https://systemd.io/JOURNAL_NATIVE_PROTOCOL/), with these fields:
+
+| field               | value                                          |
+| ------------------- | ---------------------------------------------- |
+| `MESSAGE`           | the message                                    |
+| `PRIORITY`          | `3` error, `4` warn, `6` info, `7` debug/trace |
+| `SYSLOG_IDENTIFIER` | the executable's name, or `with_identifier`    |
+| `TARGET`            | the record's target                            |
+| `CODE_MODULE`       | the record's module path, if it has one        |
+| `CODE_FILE`         | the record's file, if it has one               |
+| `CODE_LINE`         | the record's line, if it has one               |
+
+This needs the `journald` feature, and is only available on unix.
+
+`new` fails when the journal socket doesn't exist, e.g. when not running under systemd, so a
+different logger can be used instead. Records larger than the socket's maximum datagram size
+fail to send, the error is handled by the logger's [`OnError`](../enum.OnError.html) policy.
+
+# Usage
+```rust,no_run
+# use arbitrary_logger::logger::{Journald, Pretty};
+match Journald::new() {
+    Ok(logger) => arbitrary_logger::try_init(logger, log::LevelFilter::Info),
+    Err(..) => arbitrary_logger::try_init(Pretty::default(), log::LevelFilter::Info),
+}
+```
+*/
+pub struct Journald {
+    socket: UnixDatagram,
+    identifier: String,
+}
+
+impl Journald {
+    /// Connect to the journal
+    pub fn new() -> std::io::Result<Self> {
+        Self::with_socket(JOURNAL_SOCKET)
+    }
+
+    /// Connect to a journal listening on this socket, instead of `/run/systemd/journal/socket`
+    pub fn with_socket(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self {
+            socket,
+            identifier: default_identifier(),
+        })
+    }
+
+    /// Use this `SYSLOG_IDENTIFIER`, instead of the name of the executable
+    pub fn with_identifier(mut self, identifier: &str) -> Self {
+        self.identifier = identifier.to_string();
+        self
+    }
+
+    fn render(&self, record: &log::Record, buffer: &mut Vec<u8>) -> std::io::Result<()> {
+        field(buffer, "MESSAGE", &record.args().to_string())?;
+        field(buffer, "PRIORITY", priority(record.level()))?;
+        field(buffer, "SYSLOG_IDENTIFIER", &self.identifier)?;
+        field(buffer, "TARGET", record.target())?;
+        if let Some(module) = record.module_path() {
+            field(buffer, "CODE_MODULE", module)?;
+        }
+        if let Some(file) = record.file() {
+            field(buffer, "CODE_FILE", file)?;
+        }
+        if let Some(line) = record.line() {
+            field(buffer, "CODE_LINE", &line.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+impl Print for Journald {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        self.render(record, &mut buffer)?;
+        self.socket.send(&buffer).map(drop)
+    }
+}
+
+// syslog priorities, there's nothing below debug for trace
+fn priority(level: log::Level) -> &'static str {
+    match level {
+        log::Level::Error => "3",
+        log::Level::Warn => "4",
+        log::Level::Info => "6",
+        log::Level::Debug | log::Level::Trace => "7",
+    }
+}
+
+// values with a newline are written as the key, a little-endian length and the raw bytes
+fn field(buffer: &mut Vec<u8>, key: &str, value: &str) -> std::io::Result<()> {
+    if value.contains('\n') {
+        writeln!(buffer, "{}", key)?;
+        buffer.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        buffer.extend_from_slice(value.as_bytes());
+        writeln!(buffer)
+    } else {
+        writeln!(buffer, "{}={}", key, value)
+    }
+}
+
+fn default_identifier() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "arbitrary_logger".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_fields() {
+        let dir =
+            std::env::temp_dir().join(format!("arbitrary_logger_journald_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("socket");
+        let _ = std::fs::remove_file(&path);
+        let journal = UnixDatagram::bind(&path).unwrap();
+
+        let logger = Journald::with_socket(&path).unwrap().with_identifier("app");
+        logger
+            .print(
+                &log::Record::builder()
+                    .args(format_args!("two\nlines"))
+                    .level(log::Level::Warn)
+                    .target("app::db")
+                    .module_path(Some("app::db"))
+                    .file(Some("src/db.rs"))
+                    .line(Some(42))
+                    .build(),
+            )
+            .unwrap();
+
+        let mut buf = [0; 1024];
+        let n = journal.recv(&mut buf).unwrap();
+
+        let mut expected = b"MESSAGE\n".to_vec();
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        expected.extend_from_slice(
+            b"two\nlines\n\
+              PRIORITY=4\n\
+              SYSLOG_IDENTIFIER=app\n\
+              TARGET=app::db\n\
+              CODE_MODULE=app::db\n\
+              CODE_FILE=src/db.rs\n\
+              CODE_LINE=42\n",
+        );
+        assert_eq!(buf[..n], expected[..]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(Journald::with_socket(&path).is_err());
+    }
+}
@@ -0,0 +1,573 @@
+//! A JSON logger
+//!
+use crate::format::{DateStyle, FormatTime, Print, Render, Utc};
+
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+/// How the timestamp of a [`Json`](./struct.Json.html) record is written
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum JsonTimestamp {
+    /// An RFC 3339 string in UTC, with milliseconds, e.g. `"2024-06-01T12:34:56.789Z"`
+    Rfc3339,
+    /// A number of milliseconds since the UNIX epoch, e.g. `1717245296789`
+    EpochMillis,
+}
+
+/// Defaults to `Rfc3339`
+impl Default for JsonTimestamp {
+    fn default() -> Self {
+        Self::Rfc3339
+    }
+}
+
+/** A logger writing a JSON object per line
+
+By default a record looks like:
+```json
+{"timestamp":"2024-06-01T12:34:56.789Z","level":"INFO","target":"foo::bar","message":"hello"}
+```
+
+Any [`FormatTime`](../format/trait.FormatTime.html) can write the timestamp instead, with
+`with_time`, its output becomes a JSON string.
+
+The keys can be renamed, and [`Json::ecs`](#method.ecs) uses the
+[Elastic Common Schema](https://www.elastic.co/guide/en/ecs/current/index.html) names.
+
+The record's location can be added as `file` and `line` with `with_location`, and with the `kv`
+feature its key-values can be added with `with_key_values`. Booleans and numbers are written
+as JSON booleans and numbers, everything else as a string. A key-value whose key was already
+written, by the record itself or an earlier key-value, is skipped.
+*/
+pub struct Json {
+    timestamp: Option<Timestamp>,
+    timestamp_key: String,
+    level_key: String,
+    target_key: String,
+    message_key: String,
+    location: bool,
+    location_keys: (String, String),
+    #[cfg(feature = "kv")]
+    key_values: bool,
+}
+
+impl Default for Json {
+    fn default() -> Self {
+        Self {
+            timestamp: Some(Timestamp::Builtin(JsonTimestamp::default())),
+            timestamp_key: "timestamp".to_string(),
+            level_key: "level".to_string(),
+            target_key: "target".to_string(),
+            message_key: "message".to_string(),
+            location: false,
+            location_keys: ("file".to_string(), "line".to_string()),
+            #[cfg(feature = "kv")]
+            key_values: false,
+        }
+    }
+}
+
+impl Json {
+    /// Create a JSON logger with an RFC 3339 `timestamp`, `level`, `target` and `message`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a JSON logger using ECS names: `@timestamp`, `log.level`, `log.logger` and `message`
+    ///
+    /// `with_location` uses `log.origin.file.name` and `log.origin.file.line`
+    pub fn ecs() -> Self {
+        Self::new()
+            .with_timestamp_key("@timestamp")
+            .with_level_key("log.level")
+            .with_target_key("log.logger")
+            .with_location_keys("log.origin.file.name", "log.origin.file.line")
+    }
+
+    /// Write the timestamp like this
+    pub fn with_timestamp(mut self, timestamp: JsonTimestamp) -> Self {
+        self.timestamp.replace(Timestamp::Builtin(timestamp));
+        self
+    }
+
+    /// Write the timestamp with this formatter, as a JSON string
+    ///
+    /// e.g. `Json::new().with_time(Rfc3339::new(TimestampStyle::Micros))`
+    pub fn with_time<F: FormatTime + 'static>(mut self, time: F) -> Self {
+        self.timestamp.replace(Timestamp::Custom(Box::new(time)));
+        self
+    }
+
+    pub fn without_timestamp(mut self) -> Self {
+        self.timestamp.take();
+        self
+    }
+
+    /// Use this key for the timestamp, e.g. `time` or `ts`
+    pub fn with_timestamp_key(mut self, key: &str) -> Self {
+        self.timestamp_key = key.to_string();
+        self
+    }
+
+    /// Use this key for the level
+    pub fn with_level_key(mut self, key: &str) -> Self {
+        self.level_key = key.to_string();
+        self
+    }
+
+    /// Use this key for the target
+    pub fn with_target_key(mut self, key: &str) -> Self {
+        self.target_key = key.to_string();
+        self
+    }
+
+    /// Use this key for the message
+    pub fn with_message_key(mut self, key: &str) -> Self {
+        self.message_key = key.to_string();
+        self
+    }
+
+    /// Use these keys for the file and line, instead of `file` and `line`
+    pub fn with_location_keys(mut self, file: &str, line: &str) -> Self {
+        self.location_keys = (file.to_string(), line.to_string());
+        self
+    }
+
+    /// Add the record's file and line, when it has them
+    pub fn with_location(mut self) -> Self {
+        self.location = true;
+        self
+    }
+
+    pub fn without_location(mut self) -> Self {
+        self.location = false;
+        self
+    }
+
+    /// Add the record's key-values, after the message
+    #[cfg(feature = "kv")]
+    pub fn with_key_values(mut self) -> Self {
+        self.key_values = true;
+        self
+    }
+
+    #[cfg(feature = "kv")]
+    pub fn without_key_values(mut self) -> Self {
+        self.key_values = false;
+        self
+    }
+
+    fn render_at(
+        &self,
+        record: &log::Record,
+        w: &mut dyn Write,
+        now: SystemTime,
+    ) -> std::io::Result<()> {
+        write!(w, "{{")?;
+        if let Some(timestamp) = &self.timestamp {
+            let elapsed = now
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO);
+            write_str(w, &self.timestamp_key)?;
+            write!(w, ":")?;
+            match timestamp {
+                Timestamp::Builtin(JsonTimestamp::Rfc3339) => {
+                    write!(w, "\"")?;
+                    Utc::new(DateStyle::Calendar).format_millis(w, elapsed)?;
+                    write!(w, "\"")?;
+                }
+                Timestamp::Builtin(JsonTimestamp::EpochMillis) => {
+                    write!(w, "{}", elapsed.as_millis())?
+                }
+                Timestamp::Custom(time) => {
+                    write!(w, "\"")?;
+                    time.format_time_at(&mut Escape(w), now)?;
+                    write!(w, "\"")?;
+                }
+            }
+            write!(w, ",")?;
+        }
+
+        write_str(w, &self.level_key)?;
+        write!(w, ":\"{}\",", record.level())?;
+        write_str(w, &self.target_key)?;
+        write!(w, ":")?;
+        write_str(w, record.target())?;
+        write!(w, ",")?;
+        write_str(w, &self.message_key)?;
+        write!(w, ":\"")?;
+        write!(Escape(w), "{}", record.args())?;
+        write!(w, "\"")?;
+
+        if self.location {
+            let (file_key, line_key) = &self.location_keys;
+            if let Some(file) = record.file() {
+                write!(w, ",")?;
+                write_str(w, file_key)?;
+                write!(w, ":")?;
+                write_str(w, file)?;
+            }
+            if let Some(line) = record.line() {
+                write!(w, ",")?;
+                write_str(w, line_key)?;
+                write!(w, ":{}", line)?;
+            }
+        }
+
+        #[cfg(feature = "kv")]
+        if self.key_values {
+            let (file_key, line_key) = &self.location_keys;
+            let fixed = [
+                (self.timestamp.is_some(), &self.timestamp_key),
+                (true, &self.level_key),
+                (true, &self.target_key),
+                (true, &self.message_key),
+                (self.location && record.file().is_some(), file_key),
+                (self.location && record.line().is_some(), line_key),
+            ];
+            let fixed = fixed
+                .iter()
+                .filter(|(written, _)| *written)
+                .map(|(_, key)| key.as_str())
+                .collect::<Vec<_>>();
+            write_key_values(w, record, &fixed)?;
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+enum Timestamp {
+    Builtin(JsonTimestamp),
+    Custom(Box<dyn FormatTime>),
+}
+
+impl Render for Json {
+    fn render(&self, record: &log::Record, w: &mut dyn Write) -> std::io::Result<()> {
+        self.render_at(record, w, SystemTime::now())
+    }
+}
+
+impl Print for Json {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        self.render(record, &mut buffer)?;
+        std::io::stdout().lock().write_all(&buffer)
+    }
+}
+
+// skips the keys already written, so the object has no duplicate keys
+#[cfg(feature = "kv")]
+fn write_key_values(
+    w: &mut dyn Write,
+    record: &log::Record,
+    fixed: &[&str],
+) -> std::io::Result<()> {
+    struct Visit<'a, 'kvs> {
+        w: &'a mut dyn Write,
+        fixed: &'a [&'a str],
+        seen: Vec<log::kv::Key<'kvs>>,
+    }
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visit<'_, 'kvs> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if self.fixed.contains(&key.as_str()) || self.seen.contains(&key) {
+                return Ok(());
+            }
+            write!(self.w, ",")?;
+            write_str(self.w, key.as_str())?;
+            write!(self.w, ":")?;
+            if let Some(value) = value.to_bool() {
+                write!(self.w, "{}", value)?;
+            } else if let Some(value) = value.to_i64() {
+                write!(self.w, "{}", value)?;
+            } else if let Some(value) = value.to_u64() {
+                write!(self.w, "{}", value)?;
+            } else if let Some(value) = value.to_f64().filter(|v| v.is_finite()) {
+                write!(self.w, "{}", value)?;
+            } else {
+                write!(self.w, "\"")?;
+                write!(Escape(self.w), "{}", value)?;
+                write!(self.w, "\"")?;
+            }
+            self.seen.push(key);
+            Ok(())
+        }
+    }
+
+    record
+        .key_values()
+        .visit(&mut Visit {
+            w,
+            fixed,
+            seen: vec![],
+        })
+        .map_err(std::io::Error::other)
+}
+
+fn write_str(w: &mut dyn Write, s: &str) -> std::io::Result<()> {
+    write!(w, "\"")?;
+    Escape(w).write_all(s.as_bytes())?;
+    write!(w, "\"")
+}
+
+// escapes the inside of a JSON string
+struct Escape<'a>(&'a mut dyn Write);
+
+impl Write for Escape<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let escaped = |&b: &u8| b < 0x20 || b == b'"' || b == b'\\';
+        for chunk in buf.split_inclusive(escaped) {
+            match chunk.split_last() {
+                Some((&last, head)) if escaped(&last) => {
+                    self.0.write_all(head)?;
+                    match last {
+                        b'"' => self.0.write_all(b"\\\"")?,
+                        b'\\' => self.0.write_all(b"\\\\")?,
+                        b'\n' => self.0.write_all(b"\\n")?,
+                        b'\r' => self.0.write_all(b"\\r")?,
+                        b'\t' => self.0.write_all(b"\\t")?,
+                        b => write!(self.0, "\\u{:04x}", b)?,
+                    }
+                }
+                _ => self.0.write_all(chunk)?,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(json: &Json, msg: &str) -> String {
+        let mut buffer = vec![];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_millis(1_717_245_296_789);
+        json.render_at(
+            &log::Record::builder()
+                .args(format_args!("{}", msg))
+                .level(log::Level::Info)
+                .target("foo::bar")
+                .build(),
+            &mut buffer,
+            now,
+        )
+        .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn default_keys() {
+        assert_eq!(
+            render(&Json::new(), "hello"),
+            "{\"timestamp\":\"2024-06-01T12:34:56.789Z\",\"level\":\"INFO\",\
+             \"target\":\"foo::bar\",\"message\":\"hello\"}\n"
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        let json = Json::new()
+            .with_timestamp(JsonTimestamp::EpochMillis)
+            .with_timestamp_key("ts");
+        assert!(render(&json, "hello").starts_with("{\"ts\":1717245296789,\"level\""));
+
+        let json = Json::new().without_timestamp();
+        assert!(render(&json, "hello").starts_with("{\"level\""));
+    }
+
+    #[test]
+    fn custom_time() {
+        let json = Json::new().with_time(crate::format::Rfc3339::new(
+            crate::format::TimestampStyle::Micros,
+        ));
+        let value: serde_json::Value = serde_json::from_str(&render(&json, "hello")).unwrap();
+        assert_eq!(value["timestamp"], "2024-06-01T12:34:56.789000Z");
+
+        let json = Json::new()
+            .with_time(|w: &mut dyn Write| write!(w, "at \"noon\""))
+            .with_timestamp_key("time");
+        let value: serde_json::Value = serde_json::from_str(&render(&json, "hello")).unwrap();
+        assert_eq!(value["time"], "at \"noon\"");
+
+        let json = Json::new()
+            .with_time(|w: &mut dyn Write| write!(w, "noon"))
+            .with_timestamp(JsonTimestamp::EpochMillis);
+        let value: serde_json::Value = serde_json::from_str(&render(&json, "hello")).unwrap();
+        assert_eq!(value["timestamp"], 1_717_245_296_789_u64);
+    }
+
+    #[test]
+    fn structure() {
+        let mut buffer = vec![];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_millis(1_717_245_296_789);
+        let json = Json::new().with_location();
+        for (level, msg) in &[
+            (log::Level::Error, "first\nline"),
+            (log::Level::Trace, "tab\there \u{7f} \u{1b}[0m \u{2028}"),
+        ] {
+            json.render_at(
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(*level)
+                    .target("a\"b")
+                    .file(Some("src\\main.rs"))
+                    .line(Some(7))
+                    .build(),
+                &mut buffer,
+                now,
+            )
+            .unwrap();
+        }
+
+        let lines = String::from_utf8(buffer).unwrap();
+        let values = lines
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                serde_json::json!({
+                    "timestamp": "2024-06-01T12:34:56.789Z",
+                    "level": "ERROR",
+                    "target": "a\"b",
+                    "message": "first\nline",
+                    "file": "src\\main.rs",
+                    "line": 7,
+                }),
+                serde_json::json!({
+                    "timestamp": "2024-06-01T12:34:56.789Z",
+                    "level": "TRACE",
+                    "target": "a\"b",
+                    "message": "tab\there \u{7f} \u{1b}[0m \u{2028}",
+                    "file": "src\\main.rs",
+                    "line": 7,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn ecs() {
+        assert_eq!(
+            render(&Json::ecs(), "hello"),
+            "{\"@timestamp\":\"2024-06-01T12:34:56.789Z\",\"log.level\":\"INFO\",\
+             \"log.logger\":\"foo::bar\",\"message\":\"hello\"}\n"
+        );
+    }
+
+    #[test]
+    fn location() {
+        let record = log::Record::builder()
+            .args(format_args!("hello"))
+            .level(log::Level::Info)
+            .target("foo")
+            .file(Some("src/main.rs"))
+            .line(Some(42))
+            .build();
+        let render = |json: Json| {
+            let mut buffer = vec![];
+            json.without_timestamp()
+                .render(&record, &mut buffer)
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(
+            render(Json::new().with_location()),
+            "{\"level\":\"INFO\",\"target\":\"foo\",\"message\":\"hello\",\
+             \"file\":\"src/main.rs\",\"line\":42}\n"
+        );
+        assert!(render(Json::ecs().with_location())
+            .ends_with("\"log.origin.file.name\":\"src/main.rs\",\"log.origin.file.line\":42}\n"));
+        assert!(render(Json::new().with_location().without_location())
+            .ends_with("\"message\":\"hello\"}\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn key_values() {
+        let mut buffer = vec![];
+        Json::new()
+            .without_timestamp()
+            .with_key_values()
+            .render(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .key_values(&[
+                        ("ok", log::kv::Value::from(true)),
+                        ("count", log::kv::Value::from(-3)),
+                        ("ratio", log::kv::Value::from(0.5)),
+                        ("user", log::kv::Value::from("bob \"b\"")),
+                    ])
+                    .build(),
+                &mut buffer,
+            )
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "level": "INFO",
+                "target": "foo",
+                "message": "hello",
+                "ok": true,
+                "count": -3,
+                "ratio": 0.5,
+                "user": "bob \"b\"",
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn repeated_key_values() {
+        let mut buffer = vec![];
+        Json::new()
+            .without_timestamp()
+            .with_key_values()
+            .render(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .key_values(&[
+                        ("user", "bob"),
+                        ("message", "other"),
+                        ("user", "alice"),
+                        ("level", "debug"),
+                    ])
+                    .build(),
+                &mut buffer,
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"level\":\"INFO\",\"target\":\"foo\",\"message\":\"hello\",\"user\":\"bob\"}\n"
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        let out = render(&Json::new().without_timestamp(), "a \"b\"\\c\nd\u{1}é");
+        assert!(
+            out.ends_with("\"message\":\"a \\\"b\\\"\\\\c\\nd\\u0001é\"}\n"),
+            "{}",
+            out
+        );
+    }
+}
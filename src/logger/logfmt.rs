@@ -0,0 +1,265 @@
+//! A logfmt logger
+//!
+use crate::format::{FormatTime, Print, Render, Rfc3339, TimestampStyle};
+
+use std::io::Write;
+use std::time::SystemTime;
+
+/** A logger writing [logfmt](https://brandur.org/logfmt) lines, `key=value` pairs separated by spaces
+
+By default a record looks like:
+```text
+level=info target=foo::bar msg="hello world" ts=2024-06-01T12:34:56.789Z
+```
+
+The message is always quoted. Other values are only quoted when they're empty, or have spaces,
+`=`, `"` or control characters in them. Quoted values are escaped like a Rust string, e.g.
+`msg="say \"hi\"\n"`.
+
+The timestamp can be written with any [`FormatTime`](../format/trait.FormatTime.html), or left
+out. With the `kv` feature the record's key-values can be added with `with_key_values`, when a
+key appears more than once only its first value is written.
+*/
+pub struct Logfmt {
+    time: Option<Box<dyn FormatTime>>,
+    #[cfg(feature = "kv")]
+    key_values: bool,
+}
+
+impl Default for Logfmt {
+    fn default() -> Self {
+        Self {
+            time: Some(Box::new(Rfc3339::new(TimestampStyle::Millis))),
+            #[cfg(feature = "kv")]
+            key_values: false,
+        }
+    }
+}
+
+impl Logfmt {
+    /// Create a logfmt logger with `level`, `target`, `msg` and an RFC 3339 `ts`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this time formatter for `ts`
+    pub fn with_time<F: FormatTime + 'static>(mut self, time: F) -> Self {
+        self.time.replace(Box::new(time));
+        self
+    }
+
+    /// Leave out `ts`
+    pub fn without_time(mut self) -> Self {
+        self.time.take();
+        self
+    }
+
+    /// Add the record's key-values, after the timestamp
+    #[cfg(feature = "kv")]
+    pub fn with_key_values(mut self) -> Self {
+        self.key_values = true;
+        self
+    }
+
+    #[cfg(feature = "kv")]
+    pub fn without_key_values(mut self) -> Self {
+        self.key_values = false;
+        self
+    }
+
+    fn render_at(
+        &self,
+        record: &log::Record,
+        buffer: &mut Vec<u8>,
+        now: SystemTime,
+    ) -> std::io::Result<()> {
+        let level = match record.level() {
+            log::Level::Error => "error",
+            log::Level::Warn => "warn",
+            log::Level::Info => "info",
+            log::Level::Debug => "debug",
+            log::Level::Trace => "trace",
+        };
+        write!(buffer, "level={} target=", level)?;
+        write_value(buffer, record.target(), false)?;
+        write!(buffer, " msg=")?;
+        write_value(buffer, &record.args().to_string(), true)?;
+
+        if let Some(time) = self.time.as_deref() {
+            let mut ts = vec![];
+            time.format_time_at(&mut ts, now)?;
+            write!(buffer, " ts=")?;
+            write_value(buffer, &String::from_utf8_lossy(&ts), false)?;
+        }
+
+        #[cfg(feature = "kv")]
+        if self.key_values {
+            write_key_values(buffer, record)?;
+        }
+
+        writeln!(buffer)
+    }
+}
+
+/// Renders the same line as it prints
+impl Render for Logfmt {
+    fn render(&self, record: &log::Record, w: &mut dyn Write) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        self.render_at(record, &mut buffer, SystemTime::now())?;
+        w.write_all(&buffer)
+    }
+}
+
+impl Print for Logfmt {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        self.render_at(record, &mut buffer, SystemTime::now())?;
+        std::io::stdout().lock().write_all(&buffer)
+    }
+}
+
+#[cfg(feature = "kv")]
+fn write_key_values(buffer: &mut Vec<u8>, record: &log::Record) -> std::io::Result<()> {
+    struct Visit<'a, 'kvs> {
+        buffer: &'a mut Vec<u8>,
+        seen: Vec<log::kv::Key<'kvs>>,
+    }
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visit<'_, 'kvs> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if self.seen.contains(&key) {
+                return Ok(());
+            }
+            write!(self.buffer, " {}=", key)?;
+            write_value(self.buffer, &value.to_string(), false)?;
+            self.seen.push(key);
+            Ok(())
+        }
+    }
+
+    record
+        .key_values()
+        .visit(&mut Visit {
+            buffer,
+            seen: vec![],
+        })
+        .map_err(std::io::Error::other)
+}
+
+fn write_value(buffer: &mut Vec<u8>, value: &str, quote: bool) -> std::io::Result<()> {
+    let quote = quote
+        || value.is_empty()
+        || value.contains(|c: char| c.is_whitespace() || c.is_control() || c == '=' || c == '"');
+    match quote {
+        true => write!(buffer, "{:?}", value),
+        false => write!(buffer, "{}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn render(logfmt: &Logfmt, target: &str, msg: &str) -> String {
+        let mut buffer = vec![];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_millis(1_717_245_296_789);
+        logfmt
+            .render_at(
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target(target)
+                    .build(),
+                &mut buffer,
+                now,
+            )
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            render(&Logfmt::new(), "foo::bar", "hello world"),
+            "level=info target=foo::bar msg=\"hello world\" ts=2024-06-01T12:34:56.789Z\n"
+        );
+    }
+
+    #[test]
+    fn message_is_always_quoted() {
+        let logfmt = Logfmt::new().without_time();
+        assert_eq!(
+            render(&logfmt, "foo", "hello"),
+            "level=info target=foo msg=\"hello\"\n"
+        );
+        assert_eq!(
+            render(&logfmt, "foo", ""),
+            "level=info target=foo msg=\"\"\n"
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        let logfmt = Logfmt::new().without_time();
+        assert_eq!(
+            render(&logfmt, "foo", "say \"hi\"\\\nbye"),
+            "level=info target=foo msg=\"say \\\"hi\\\"\\\\\\nbye\"\n"
+        );
+        for (target, expected) in &[
+            ("a b", "\"a b\""),
+            ("a=b", "\"a=b\""),
+            ("a\"b", "\"a\\\"b\""),
+            ("a\tb", "\"a\\tb\""),
+            ("", "\"\""),
+            ("a::b-c.d", "a::b-c.d"),
+            ("é", "é"),
+        ] {
+            assert_eq!(
+                render(&logfmt, target, "hi"),
+                format!("level=info target={} msg=\"hi\"\n", expected)
+            );
+        }
+
+        let logfmt = Logfmt::new().with_time(|w: &mut dyn Write| write!(w, "at noon"));
+        assert!(render(&logfmt, "foo", "hi").ends_with(" ts=\"at noon\"\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn key_values() {
+        let record = |logfmt: Logfmt| {
+            let mut buffer = vec![];
+            logfmt
+                .without_time()
+                .render(
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(log::Level::Warn)
+                        .target("foo")
+                        .key_values(&[
+                            ("user", log::kv::Value::from("Bob Smith")),
+                            ("count", log::kv::Value::from(3)),
+                            ("ok", log::kv::Value::from(true)),
+                            ("user", log::kv::Value::from("alice")),
+                        ])
+                        .build(),
+                    &mut buffer,
+                )
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(
+            record(Logfmt::new().with_key_values()),
+            "level=warn target=foo msg=\"hello\" user=\"Bob Smith\" count=3 ok=true\n"
+        );
+        assert_eq!(
+            record(Logfmt::new()),
+            "level=warn target=foo msg=\"hello\"\n"
+        );
+    }
+}
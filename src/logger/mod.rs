@@ -1,4 +1,66 @@
 //! Different logger styles
 
+use crate::{filtered::Filtered, format::Print, InitError, IntoLevelFilter};
+
 mod pretty;
-pub use pretty::{Pretty, PrettyBuilder};
+pub use pretty::{BuildError, Field, Pretty, PrettyBuilder};
+
+mod tsv;
+pub use tsv::Tsv;
+
+#[cfg(feature = "kv")]
+mod access_log;
+#[cfg(feature = "kv")]
+pub use access_log::AccessLog;
+
+mod json;
+pub use json::{Json, JsonTimestamp};
+
+mod logfmt;
+pub use logfmt::Logfmt;
+
+mod multi;
+pub use multi::Multi;
+
+mod non_blocking;
+pub use non_blocking::{FlushGuard, NonBlocking, Stats};
+
+mod dedup;
+pub use dedup::Dedup;
+
+mod counting;
+pub use counting::{Counters, Counting, LevelCounts};
+
+mod buffered;
+pub use buffered::Buffered;
+
+mod udp;
+pub use udp::UdpSink;
+
+#[cfg(all(unix, feature = "journald"))]
+mod journald;
+#[cfg(all(unix, feature = "journald"))]
+pub use journald::Journald;
+
+mod rewrite;
+pub use rewrite::TargetRewrite;
+
+/// Build a logger with a minimum level and optional filters, without installing it
+///
+/// The returned logger can be driven directly with `log::Log::log`, e.g. in tests
+pub fn build<F, L>(
+    format: F,
+    min_level: L,
+    filters: impl Into<Option<Filtered>>,
+) -> Result<impl log::Log, InitError>
+where
+    F: Print + 'static,
+    L: IntoLevelFilter,
+{
+    let builder = crate::builder(format).with_min_level(min_level);
+    match filters.into() {
+        Some(filters) => builder.with_filters(filters),
+        None => builder,
+    }
+    .build()
+}
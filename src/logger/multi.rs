@@ -0,0 +1,169 @@
+//! A logger writing to several loggers
+//!
+use crate::format::Print;
+
+/** A logger passing each record to several loggers
+
+Every logger is configured on its own, e.g. a short colored line on the console and JSON with
+the location and key-values in a file. Each logger owns its configuration and only gets a
+shared reference to the record, so nothing is shared between them.
+
+Every logger gets every record, even if an earlier one failed. The first error is returned.
+
+# Usage
+```rust,no_run
+# use arbitrary_logger::logger::{Buffered, Json, Multi, Pretty};
+# use std::time::Duration;
+let file = std::fs::File::create("app.log").unwrap();
+let logger = Multi::new()
+    .with(Pretty::builder().with_level().build())
+    .with(Buffered::new(
+        Json::new().with_location(),
+        file,
+        Duration::from_millis(100),
+    ));
+arbitrary_logger::try_init(logger, log::LevelFilter::Trace);
+```
+*/
+#[derive(Default)]
+pub struct Multi {
+    loggers: Vec<Box<dyn Print>>,
+}
+
+impl Multi {
+    /// Create a logger without any loggers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also pass records to this logger
+    pub fn with<P: Print + 'static>(mut self, logger: P) -> Self {
+        self.loggers.push(Box::new(logger));
+        self
+    }
+}
+
+impl Print for Multi {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        self.loggers
+            .iter()
+            .map(|logger| logger.print(record))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.loggers
+            .iter()
+            .map(|logger| logger.flush())
+            .fold(Ok(()), Result::and)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Render;
+    use crate::logger::{Buffered, Json, Pretty};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    impl Shared {
+        fn lines(&self) -> Vec<String> {
+            let data = self.0.lock().unwrap();
+            String::from_utf8_lossy(&data)
+                .lines()
+                .map(Into::into)
+                .collect()
+        }
+    }
+
+    // a console stand-in, rendering like it'd print
+    struct Console<R>(R, Shared);
+    impl<R: Render> Print for Console<R> {
+        fn print(&self, record: &log::Record) -> std::io::Result<()> {
+            self.0.render(record, &mut self.1.clone())
+        }
+    }
+
+    #[test]
+    fn console_and_file() {
+        let (console, file) = (Shared::default(), Shared::default());
+        let logger = Multi::new()
+            .with(Console(
+                Pretty::builder().with_level().build(),
+                console.clone(),
+            ))
+            .with(Buffered::new(
+                Json::new().without_timestamp().with_location(),
+                file.clone(),
+                std::time::Duration::from_secs(60),
+            ));
+
+        for (msg, line) in &[("hello", 10), ("world", 20)] {
+            logger
+                .print(
+                    &log::Record::builder()
+                        .args(format_args!("{}", msg))
+                        .level(log::Level::Warn)
+                        .target("app")
+                        .file(Some("src/main.rs"))
+                        .line(Some(*line))
+                        .build(),
+                )
+                .unwrap();
+        }
+        assert_eq!(console.lines(), vec!["WARN  hello", "WARN  world"]);
+        assert!(file.lines().is_empty(), "buffered until flushed");
+
+        logger.flush().unwrap();
+        let file = file
+            .lines()
+            .iter()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            file,
+            vec![
+                serde_json::json!({
+                    "level": "WARN", "target": "app", "message": "hello",
+                    "file": "src/main.rs", "line": 10,
+                }),
+                serde_json::json!({
+                    "level": "WARN", "target": "app", "message": "world",
+                    "file": "src/main.rs", "line": 20,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_logger() {
+        struct Broken;
+        impl Print for Broken {
+            fn print(&self, _: &log::Record) -> std::io::Result<()> {
+                Err(std::io::Error::other("broken"))
+            }
+        }
+
+        let console = Shared::default();
+        let logger = Multi::new()
+            .with(Broken)
+            .with(Console(Pretty::builder().build(), console.clone()));
+
+        let err = logger
+            .print(&log::Record::builder().args(format_args!("hello")).build())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "broken");
+        assert_eq!(console.lines(), vec!["hello"]);
+    }
+}
@@ -0,0 +1,414 @@
+//! A non-blocking logger
+//!
+use crate::format::Print;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often the worker reports dropped records
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a `FlushGuard` waits for the worker by default
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/** Counters for a [`NonBlocking`](./struct.NonBlocking.html) logger
+
+Each counter only ever increases. They are updated with `Ordering::Relaxed`, so each one is
+accurate on its own but a snapshot of several of them may be momentarily inconsistent
+(e.g. a record can show up as `written` before it shows up as `enqueued`).
+*/
+#[derive(Debug, Default)]
+pub struct Stats {
+    enqueued: AtomicU64,
+    written: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Stats {
+    /// Records handed to the worker
+    pub fn enqueued(&self) -> u64 {
+        self.enqueued.load(Ordering::Relaxed)
+    }
+
+    /// Records the worker has printed
+    pub fn written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    /// Records dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/** A logger that hands records off to a worker thread
+
+The record is copied (its level, target, location, formatted message and key-values, as
+strings) and queued, the worker then prints it with the wrapped logger. Timestamps are taken
+when the worker prints.
+
+When the queue is full the record is dropped rather than blocking the caller. Drops are
+counted in [`Stats`](./struct.Stats.html) and the worker reports them, at most once a second,
+with a `Warn` record targeted at `arbitrary_logger`.
+
+# Usage
+```rust
+# use arbitrary_logger::logger::{NonBlocking, Pretty};
+let (logger, _guard) = NonBlocking::new(Pretty::default(), 1024);
+arbitrary_logger::try_init(logger, log::LevelFilter::Trace);
+// keep `_guard` alive until the end of `main`
+```
+*/
+pub struct NonBlocking {
+    sender: SyncSender<Message>,
+    stats: Arc<Stats>,
+}
+
+impl NonBlocking {
+    /// Spawn a worker printing with `inner`, queueing up to `capacity` records
+    ///
+    /// The returned [`FlushGuard`](./struct.FlushGuard.html) must be kept alive, see its docs.
+    pub fn new<P: Print + 'static>(inner: P, capacity: usize) -> (Self, FlushGuard) {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(capacity);
+        let (done, finished) = std::sync::mpsc::channel();
+        let stats = Arc::<Stats>::default();
+
+        let worker = Worker {
+            inner,
+            stats: Arc::clone(&stats),
+            reported: 0,
+            last_report: None,
+        };
+        let handle = std::thread::Builder::new()
+            .name("arbitrary_logger".into())
+            .spawn(move || {
+                worker.run(receiver);
+                let _ = done.send(());
+            })
+            .expect("spawn non-blocking worker");
+
+        let guard = FlushGuard {
+            sender: sender.clone(),
+            finished,
+            handle: Some(handle),
+            timeout: FLUSH_TIMEOUT,
+        };
+        (Self { sender, stats }, guard)
+    }
+
+    /// Get the counters for this logger
+    pub fn stats(&self) -> Arc<Stats> {
+        Arc::clone(&self.stats)
+    }
+}
+
+impl Print for NonBlocking {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        match self.sender.try_send(Message::Record(record.into())) {
+            Ok(..) => {
+                self.stats.enqueued.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Full(..)) => {
+                self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(..)) => Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "non-blocking worker has stopped",
+            )),
+        }
+    }
+}
+
+/** Flushes a [`NonBlocking`](./struct.NonBlocking.html) logger when dropped
+
+Dropping the guard tells the worker to print everything queued before it and then stop, it
+waits up to a timeout (1 second by default) for that to happen. Records logged after this are
+not printed, the `NonBlocking` logger returns an error for them.
+
+Holding the guard until the end of `main` is what makes the logger durable: the worker is not
+joined when the process exits, so without the guard anything still queued is lost. If the guard
+is leaked (e.g. with `std::mem::forget`) the worker just keeps running.
+
+When dropped during a panic, the guard only tries to signal the worker once and still never
+waits longer than the timeout.
+*/
+pub struct FlushGuard {
+    sender: SyncSender<Message>,
+    finished: Receiver<()>,
+    handle: Option<std::thread::JoinHandle<()>>,
+    timeout: Duration,
+}
+
+impl FlushGuard {
+    /// Wait at most `timeout` for the worker when dropped
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            match self.sender.try_send(Message::Shutdown) {
+                Err(TrySendError::Full(..))
+                    if !std::thread::panicking() && Instant::now() < deadline =>
+                {
+                    std::thread::sleep(Duration::from_millis(1))
+                }
+                Err(TrySendError::Full(..)) => return,
+                _ => break,
+            }
+        }
+
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if self.finished.recv_timeout(timeout).is_ok() {
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+enum Message {
+    Record(OwnedRecord),
+    Shutdown,
+}
+
+struct Worker<P> {
+    inner: P,
+    stats: Arc<Stats>,
+    reported: u64,
+    last_report: Option<Instant>,
+}
+
+impl<P: Print> Worker<P> {
+    fn run(mut self, receiver: Receiver<Message>) {
+        loop {
+            match receiver.recv_timeout(REPORT_INTERVAL) {
+                Ok(Message::Record(record)) => {
+                    self.report_dropped(false);
+                    let _ = record.print(&self.inner);
+                    self.stats.written.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(RecvTimeoutError::Timeout) => self.report_dropped(false),
+                Ok(Message::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        self.report_dropped(true);
+    }
+
+    fn report_dropped(&mut self, force: bool) {
+        let dropped = self.stats.dropped();
+        let due = force
+            || self
+                .last_report
+                .is_none_or(|t| t.elapsed() >= REPORT_INTERVAL);
+        if dropped == self.reported || !due {
+            return;
+        }
+
+        let _ = self.inner.print(
+            &log::Record::builder()
+                .args(format_args!(
+                    "dropped {} records, the queue was full",
+                    dropped - self.reported
+                ))
+                .level(log::Level::Warn)
+                .target("arbitrary_logger")
+                .build(),
+        );
+        self.reported = dropped;
+        self.last_report.replace(Instant::now());
+    }
+}
+
+struct OwnedRecord {
+    level: log::Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    #[cfg(feature = "kv")]
+    key_values: Vec<(String, String)>,
+}
+
+impl OwnedRecord {
+    fn print(&self, inner: &dyn Print) -> std::io::Result<()> {
+        let mut record = log::Record::builder();
+        record
+            .level(self.level)
+            .target(&self.target)
+            .module_path(self.module_path.as_deref())
+            .file(self.file.as_deref())
+            .line(self.line);
+
+        #[cfg(feature = "kv")]
+        let key_values = self
+            .key_values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "kv")]
+        record.key_values(&key_values);
+
+        inner.print(&record.args(format_args!("{}", self.message)).build())
+    }
+}
+
+#[cfg(feature = "kv")]
+fn owned_key_values(record: &log::Record<'_>) -> Vec<(String, String)> {
+    struct Visit<'a>(&'a mut Vec<(String, String)>);
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visit<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    let mut key_values = vec![];
+    let _ = record.key_values().visit(&mut Visit(&mut key_values));
+    key_values
+}
+
+impl From<&log::Record<'_>> for OwnedRecord {
+    fn from(record: &log::Record<'_>) -> Self {
+        Self {
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            module_path: record.module_path().map(ToString::to_string),
+            file: record.file().map(ToString::to_string),
+            line: record.line(),
+            #[cfg(feature = "kv")]
+            key_values: owned_key_values(record),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Capture;
+    use std::sync::{mpsc, Mutex};
+
+    struct Blocking {
+        started: Mutex<mpsc::Sender<()>>,
+        release: Mutex<mpsc::Receiver<()>>,
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Print for Blocking {
+        fn print(&self, record: &log::Record) -> std::io::Result<()> {
+            let _ = self.started.lock().unwrap().send(());
+            let _ = self.release.lock().unwrap().recv();
+            self.lines.lock().unwrap().push(record.args().to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn drops_when_full() {
+        let (started_tx, started) = mpsc::channel();
+        let (release, release_rx) = mpsc::channel();
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+
+        let (logger, guard) = NonBlocking::new(
+            Blocking {
+                started: Mutex::new(started_tx),
+                release: Mutex::new(release_rx),
+                lines: Arc::clone(&lines),
+            },
+            1,
+        );
+        let stats = logger.stats();
+
+        let print = |msg: &str| {
+            logger
+                .print(&log::Record::builder().args(format_args!("{}", msg)).build())
+                .unwrap()
+        };
+
+        print("first");
+        started.recv().unwrap(); // the worker is now blocked printing the first record
+        print("second"); // fills the queue
+        print("third"); // dropped
+
+        assert_eq!(stats.enqueued(), 2);
+        assert_eq!(stats.dropped(), 1);
+
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        drop(guard.with_timeout(Duration::from_secs(5)));
+
+        assert_eq!(stats.written(), 2);
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["first", "dropped 1 records, the queue was full", "second"]
+        );
+    }
+
+    #[test]
+    fn flush_on_drop() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let capture = Capture::with_format(&lines, |record| {
+            std::thread::sleep(Duration::from_millis(1));
+            record.args().to_string()
+        });
+        let (logger, guard) = NonBlocking::new(capture, 64);
+        for i in 0..50 {
+            logger
+                .print(&log::Record::builder().args(format_args!("{}", i)).build())
+                .unwrap();
+        }
+
+        drop(guard.with_timeout(Duration::from_secs(5)));
+        assert_eq!(lines.lock().unwrap().len(), 50);
+        assert!(logger
+            .print(&log::Record::builder().args(format_args!("late")).build())
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn key_values() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let capture = Capture::with_format(&lines, |record| {
+            let kvs = record.key_values();
+            let get = |key| kvs.get(log::kv::Key::from_str(key)).map(|v| v.to_string());
+            format!(
+                "{} {:?} {:?} {}",
+                record.args(),
+                get("user"),
+                get("attempt"),
+                kvs.count()
+            )
+        });
+        let (logger, guard) = NonBlocking::new(capture, 4);
+        logger
+            .print(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .key_values(&[("user", "alice"), ("attempt", "3")])
+                    .build(),
+            )
+            .unwrap();
+
+        drop(guard.with_timeout(Duration::from_secs(5)));
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec![r#"hello Some("alice") Some("3") 2"#]
+        );
+    }
+}
@@ -1,18 +1,86 @@
 //! A pretty logger
 //!
 use crate::format::{
-    self, FormatTime, Print, RecordColorConfig, Timestamp, TimestampStyle, Uptime,
+    self, DateStyle, Delta, FormatTime, LevelLabels, Print, RecordColorConfig, Render, Timestamp,
+    TimestampStyle, Uptime, Utc,
 };
 
-/// A pretty logger
+/// The sequence number of the last record, shared by every Pretty logger
+static SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// How many digits a sequence number is padded to by default
+const DEFAULT_SEQUENCE_WIDTH: usize = 5;
+
+/// How long a target stays grouped by default
+const DEFAULT_GROUPING_WINDOW: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// A part of a line, for [`PrettyBuilder::with_field_order`](./struct.PrettyBuilder.html#method.with_field_order)
+///
+/// Fields are written in order, with the separator between them
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Field {
+    /// A symbol for the level
+    Gutter,
+    Level,
+    Target,
+    ModulePath,
+    /// The `file:line` of the log site
+    Location,
+    /// Needs a time formatter, e.g. with `with_time`
+    Timestamp,
+    /// The time since the previous record
+    Delta,
+    Sequence,
+    ThreadName,
+    ThreadId,
+    /// Starts a new line, so it isn't preceded by a separator. Needs a continuation string
+    Continuation,
+    Message,
+}
+
+#[cfg(feature = "color")]
+type Buffer = termcolor::Buffer;
+#[cfg(not(feature = "color"))]
+type Buffer = Vec<u8>;
+
+/** A pretty logger
+
+Colors are used when stdout is a terminal, and not when it's piped to a file. Unless a color
+choice was set with the builder, this can be overridden with the `RUST_LOG_STYLE`
+environment variable, like `env_logger`: `never`, `always` or `auto`. Otherwise, following
+<https://no-color.org>, a non-empty `NO_COLOR` turns colors off, and then a `CLICOLOR_FORCE`
+other than `0` turns them on even when stdout isn't a terminal. The environment is read when
+the logger is built.
+*/
 pub struct Pretty {
+    prefix: Option<String>,
     continuation: Option<String>,
+    separator: String,
+    target_brackets: (String, String),
+    target_width: usize,
+    max_target_len: usize,
+    level_labels: LevelLabels,
+    gutter: LevelLabels,
+    escape_control: bool,
+    continuation_indent: bool,
+    level_colored_continuation: bool,
+    level_colored_message: bool,
+    crlf: bool,
+    wrap: usize,
+    sequence_width: usize,
+    target_grouping: Option<std::time::Duration>,
+    last_target: std::sync::Mutex<Option<(String, std::time::Instant)>>,
     time: Option<Box<dyn FormatTime>>,
+    #[cfg(feature = "kv")]
+    record_time_key: Option<String>,
+    #[cfg(feature = "kv")]
+    key_values: bool,
+    delta: Option<Delta>,
+    layout: Vec<Field>,
 
-    #[allow(dead_code)]
-    use_color: bool,
-    level: bool,
-    target: bool,
+    #[cfg(feature = "color")]
+    color_choice: termcolor::ColorChoice,
 
     record_colors: RecordColorConfig,
 }
@@ -20,12 +88,33 @@ pub struct Pretty {
 impl Default for Pretty {
     fn default() -> Self {
         Self {
+            prefix: None,
             continuation: None,
+            separator: " ".to_string(),
+            target_brackets: ("[".to_string(), "]".to_string()),
+            target_width: 0,
+            max_target_len: 0,
+            level_labels: LevelLabels::default(),
+            gutter: LevelLabels::gutter(),
+            escape_control: false,
+            continuation_indent: false,
+            level_colored_continuation: false,
+            level_colored_message: false,
+            crlf: false,
+            wrap: 0,
+            sequence_width: DEFAULT_SEQUENCE_WIDTH,
+            target_grouping: None,
+            last_target: Default::default(),
             time: None,
+            #[cfg(feature = "kv")]
+            record_time_key: None,
+            #[cfg(feature = "kv")]
+            key_values: false,
+            delta: None,
+            layout: vec![Field::Level, Field::Target, Field::Message],
 
-            use_color: true,
-            level: true,
-            target: true,
+            #[cfg(feature = "color")]
+            color_choice: color_choice(None),
 
             record_colors: Default::default(),
         }
@@ -37,55 +126,329 @@ impl Pretty {
     pub fn builder() -> PrettyBuilder {
         PrettyBuilder::default()
     }
+
+    // whether this target is the same as the last one, within the grouping window
+    fn is_grouped(&self, target: &str) -> bool {
+        let window = match self.target_grouping {
+            Some(window) => window,
+            None => return false,
+        };
+
+        let now = std::time::Instant::now();
+        let mut last = self
+            .last_target
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let grouped = matches!(
+            &*last,
+            Some((last, at)) if last == target && now.duration_since(*at) < window
+        );
+        last.replace((target.to_string(), now));
+        grouped
+    }
+
+    fn render(&self, record: &log::Record, buffer: &mut Buffer) -> std::io::Result<()> {
+        let writer = format::new_writer(record, self.record_colors)
+            .with_separator(&self.separator)
+            .with_target_brackets(&self.target_brackets.0, &self.target_brackets.1)
+            .with_target_width(self.target_width)
+            .with_max_target_len(self.max_target_len)
+            .with_level_labels(self.level_labels)
+            .with_control_char_escaping(self.escape_control)
+            .with_level_colored_continuation(self.level_colored_continuation)
+            .with_level_colored_message(self.level_colored_message)
+            .with_crlf(self.crlf);
+        #[cfg(feature = "kv")]
+        let writer = writer.with_key_values(self.key_values);
+        let buffer = &mut Column::new(buffer);
+
+        // the separator only goes between fields, so a lone message is flush-left
+        let mut written = false;
+        if let Some(prefix) = &self.prefix {
+            writer.prefix(buffer, prefix)?;
+            written = true;
+        }
+        for field in &self.layout {
+            if written && *field != Field::Continuation {
+                writer.separator(buffer)?;
+            }
+            self.field(*field, writer, record, buffer)?;
+            written = true;
+        }
+        writer.end(buffer)
+    }
+
+    fn field(
+        &self,
+        field: Field,
+        writer: format::Writer<'_, '_>,
+        record: &log::Record,
+        buffer: &mut Column<'_, Buffer>,
+    ) -> std::io::Result<()> {
+        match field {
+            Field::Gutter => writer.gutter(buffer, &self.gutter),
+            Field::Level => writer.level(buffer),
+            Field::Target if self.is_grouped(record.target()) => writer.blank_target(buffer),
+            Field::Target => writer.target(buffer),
+            Field::ModulePath => writer.module_path(buffer),
+            Field::Location => writer.location(buffer),
+            Field::Timestamp => match self.time.as_deref() {
+                #[cfg(feature = "kv")]
+                Some(time) => match self.record_time(record) {
+                    Some(at) => writer.timestamp(buffer, &At(time, at)),
+                    None => writer.timestamp(buffer, time),
+                },
+                #[cfg(not(feature = "kv"))]
+                Some(time) => writer.timestamp(buffer, time),
+                None => Ok(()),
+            },
+            Field::Delta => match &self.delta {
+                Some(delta) => writer.timestamp(buffer, delta),
+                None => Ok(()),
+            },
+            Field::Sequence => {
+                let seq = SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+                writer.sequence(buffer, seq, self.sequence_width)
+            }
+            Field::ThreadName => writer.thread_name(buffer),
+            Field::ThreadId => writer.thread_id(buffer),
+            Field::Continuation => match &self.continuation {
+                Some(cont) => writer.continuation(buffer, cont),
+                None => Ok(()),
+            },
+            Field::Message => self.message(writer, buffer),
+        }
+    }
+
+    // the time carried by the record, as seconds since the UNIX epoch
+    #[cfg(feature = "kv")]
+    fn record_time(&self, record: &log::Record) -> Option<std::time::SystemTime> {
+        let key = self.record_time_key.as_deref()?;
+        let value = record.key_values().get(log::kv::Key::from_str(key))?;
+        let secs = match value.to_u64() {
+            Some(secs) => std::time::Duration::from_secs(secs),
+            None => std::time::Duration::try_from_secs_f64(value.to_f64()?).ok()?,
+        };
+        Some(std::time::UNIX_EPOCH + secs)
+    }
+
+    fn message(
+        &self,
+        writer: format::Writer<'_, '_>,
+        buffer: &mut Column<'_, Buffer>,
+    ) -> std::io::Result<()> {
+        if self.wrap > 0 {
+            let cont = self
+                .continuation
+                .as_deref()
+                .unwrap_or_else(|| crate::default_continuation());
+            return writer.wrapped_message(buffer, buffer.column, self.wrap, cont);
+        }
+        if self.continuation_indent {
+            let indent = buffer.column;
+            return writer.with_message_indent(indent).message(buffer);
+        }
+        match &self.continuation {
+            Some(cont) if self.layout.contains(&Field::Continuation) => {
+                writer.with_message_continuation(cont).message(buffer)
+            }
+            _ => writer.message(buffer),
+        }
+    }
+}
+
+/// Renders without colors
+impl Render for Pretty {
+    fn render(&self, record: &log::Record, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        #[cfg(not(feature = "color"))]
+        {
+            let mut buffer = Buffer::new();
+            Pretty::render(self, record, &mut buffer)?;
+            w.write_all(&buffer)
+        }
+
+        #[cfg(feature = "color")]
+        {
+            let mut buffer = Buffer::no_color();
+            Pretty::render(self, record, &mut buffer)?;
+            w.write_all(buffer.as_slice())
+        }
+    }
 }
 
 impl Print for Pretty {
     #[inline]
     fn print(&self, record: &log::Record) -> std::io::Result<()> {
-        let writer = format::new_writer(record, self.record_colors);
-
         #[cfg(not(feature = "color"))]
-        let out = std::io::stdout();
-        #[cfg(not(feature = "color"))]
-        let mut buffer = out.lock();
+        {
+            use std::io::Write as _;
+            let mut buffer = Buffer::new();
+            Pretty::render(self, record, &mut buffer)?;
+            std::io::stdout().lock().write_all(&buffer)
+        }
 
         #[cfg(feature = "color")]
-        let buf_writer = termcolor::BufferWriter::stdout(if self.use_color {
-            termcolor::ColorChoice::Auto
-        } else {
-            termcolor::ColorChoice::Never
-        });
-        #[cfg(feature = "color")]
-        let mut buffer = buf_writer.buffer();
-
-        if self.level {
-            writer.level(&mut buffer)?;
+        {
+            let buf_writer = termcolor::BufferWriter::stdout(self.color_choice);
+            let mut buffer = buf_writer.buffer();
+            Pretty::render(self, record, &mut buffer)?;
+            buf_writer.print(&buffer)
         }
-        if self.target {
-            writer.target(&mut buffer)?;
-        }
-        if let Some(time) = self.time.as_deref() {
-            writer.timestamp(&mut buffer, time)?;
+    }
+}
+
+/// The environment variable overriding the default color choice
+#[cfg(feature = "color")]
+const LOG_STYLE_KEY: &str = "RUST_LOG_STYLE";
+
+/// The environment variable turning colors off when it isn't empty, see <https://no-color.org>
+#[cfg(feature = "color")]
+const NO_COLOR_KEY: &str = "NO_COLOR";
+
+/// The environment variable turning colors on when it isn't `0`, even when not on a terminal
+#[cfg(feature = "color")]
+const CLICOLOR_FORCE_KEY: &str = "CLICOLOR_FORCE";
+
+/// The environment the color choice depends on, including whether the sink is a terminal
+#[cfg(feature = "color")]
+#[derive(Default)]
+struct ColorEnv {
+    log_style: Option<String>,
+    no_color: bool,
+    clicolor_force: bool,
+    is_terminal: bool,
+}
+
+#[cfg(feature = "color")]
+impl ColorEnv {
+    fn read() -> Self {
+        use std::io::IsTerminal as _;
+        Self {
+            log_style: std::env::var(LOG_STYLE_KEY).ok(),
+            no_color: std::env::var_os(NO_COLOR_KEY).is_some_and(|s| !s.is_empty()),
+            clicolor_force: std::env::var_os(CLICOLOR_FORCE_KEY).is_some_and(|s| s != "0"),
+            is_terminal: std::io::stdout().is_terminal(),
         }
-        if let Some(ref cont) = self.continuation {
-            writer.continuation(&mut buffer, &cont)?;
+    }
+}
+
+/// Decide the color choice for stdout from the environment, once, when the logger is built
+#[cfg(feature = "color")]
+fn color_choice(explicit: Option<termcolor::ColorChoice>) -> termcolor::ColorChoice {
+    resolve_color_choice(explicit, &ColorEnv::read())
+}
+
+/// An explicit choice wins over `RUST_LOG_STYLE`, then `NO_COLOR`, then `CLICOLOR_FORCE`, and
+/// finally colors are only used on a terminal, which an explicit `Auto` still requires
+#[cfg(feature = "color")]
+fn resolve_color_choice(
+    explicit: Option<termcolor::ColorChoice>,
+    env: &ColorEnv,
+) -> termcolor::ColorChoice {
+    use termcolor::ColorChoice;
+    match (explicit, env.log_style.as_deref().map(str::trim)) {
+        (Some(ColorChoice::Auto), ..) if !env.is_terminal => ColorChoice::Never,
+        (Some(choice), ..) => choice,
+        (None, Some(style)) if style.eq_ignore_ascii_case("never") => ColorChoice::Never,
+        (None, Some(style)) if style.eq_ignore_ascii_case("always") => ColorChoice::Always,
+        _ if env.no_color => ColorChoice::Never,
+        _ if env.clicolor_force => ColorChoice::Always,
+        _ if env.is_terminal => ColorChoice::Auto,
+        _ => ColorChoice::Never,
+    }
+}
+
+/// Formats the time at a fixed point, rather than now
+#[cfg(feature = "kv")]
+struct At<'a>(&'a dyn FormatTime, std::time::SystemTime);
+
+#[cfg(feature = "kv")]
+impl FormatTime for At<'_> {
+    fn format_time(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.0.format_time_at(w, self.1)
+    }
+}
+
+/// Tracks the current column, ignoring colors
+struct Column<'a, W: ?Sized> {
+    inner: &'a mut W,
+    column: usize,
+}
+
+impl<'a, W: ?Sized> Column<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        Self { inner, column: 0 }
+    }
+}
+
+impl<W: ?Sized + std::io::Write> std::io::Write for Column<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        for &b in &buf[..n] {
+            match b {
+                b'\n' => self.column = 0,
+                // skip utf-8 continuation bytes
+                b if b & 0xC0 == 0x80 => {}
+                _ => self.column += 1,
+            }
         }
-        writer.message(&mut buffer)?;
+        Ok(n)
+    }
 
-        #[cfg(feature = "color")]
-        buf_writer.print(&buffer)?;
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "color")]
+impl<W: ?Sized + termcolor::WriteColor> termcolor::WriteColor for Column<'_, W> {
+    fn supports_color(&self) -> bool {
+        self.inner.supports_color()
+    }
 
-        Ok(())
+    fn set_color(&mut self, spec: &termcolor::ColorSpec) -> std::io::Result<()> {
+        self.inner.set_color(spec)
+    }
+
+    fn reset(&mut self) -> std::io::Result<()> {
+        self.inner.reset()
     }
 }
 
 /// Configure a Pretty logger
 pub struct PrettyBuilder {
     time: Option<Box<dyn FormatTime>>,
+    #[cfg(feature = "kv")]
+    record_time_key: Option<String>,
+    #[cfg(feature = "kv")]
+    key_values: bool,
+    delta: bool,
     target: bool,
     level: bool,
-    use_color: bool,
+    #[cfg(feature = "color")]
+    use_color: Option<format::ColorChoice>,
+    prefix: Option<String>,
     continuation: Option<String>,
+    separator: String,
+    target_brackets: (String, String),
+    target_width: usize,
+    max_target_len: usize,
+    level_labels: LevelLabels,
+    gutter: Option<LevelLabels>,
+    escape_control: bool,
+    continuation_indent: bool,
+    level_colored_continuation: bool,
+    level_colored_message: bool,
+    crlf: bool,
+    message_first: bool,
+    wrap: usize,
+    sequence: Option<usize>,
+    field_order: Option<Vec<Field>>,
+    thread_name: bool,
+    thread_id: bool,
+    module_path: bool,
+    location: bool,
+    target_grouping: Option<std::time::Duration>,
     record_colors: RecordColorConfig,
 }
 
@@ -93,11 +456,38 @@ impl Default for PrettyBuilder {
     fn default() -> Self {
         let (time, target, level, continuation, record_colors) = Default::default();
         Self {
-            use_color: true,
+            #[cfg(feature = "color")]
+            use_color: None,
+            prefix: None,
             time,
+            #[cfg(feature = "kv")]
+            record_time_key: None,
+            #[cfg(feature = "kv")]
+            key_values: false,
+            delta: false,
             target,
             level,
             continuation,
+            separator: " ".to_string(),
+            target_brackets: ("[".to_string(), "]".to_string()),
+            target_width: 0,
+            max_target_len: 0,
+            level_labels: LevelLabels::default(),
+            gutter: None,
+            escape_control: false,
+            continuation_indent: false,
+            level_colored_continuation: false,
+            level_colored_message: false,
+            crlf: false,
+            message_first: false,
+            wrap: 0,
+            sequence: None,
+            field_order: None,
+            thread_name: false,
+            thread_id: false,
+            module_path: false,
+            location: false,
+            target_grouping: None,
             record_colors,
         }
     }
@@ -110,15 +500,54 @@ impl PrettyBuilder {
         self
     }
 
+    /// Color the continuation marker with the level's color
+    #[cfg(feature = "color")]
+    pub fn with_level_colored_continuation(mut self) -> Self {
+        self.level_colored_continuation = true;
+        self
+    }
+
+    /// Color the continuation marker with its own color (the default)
     #[cfg(feature = "color")]
-    pub fn with_color(mut self) -> Self {
-        self.use_color = true;
+    pub fn without_level_colored_continuation(mut self) -> Self {
+        self.level_colored_continuation = false;
         self
     }
 
+    /// Color the message with the level's color, e.g. red for errors
     #[cfg(feature = "color")]
-    pub fn without_color(mut self) -> Self {
-        self.use_color = false;
+    pub fn with_level_colored_message(mut self) -> Self {
+        self.level_colored_message = true;
+        self
+    }
+
+    /// Color the message with its own color (the default)
+    #[cfg(feature = "color")]
+    pub fn without_level_colored_message(mut self) -> Self {
+        self.level_colored_message = false;
+        self
+    }
+
+    /// Use colors when stdout is a terminal, ignoring the environment
+    #[cfg(feature = "color")]
+    pub fn with_color(self) -> Self {
+        self.with_color_choice(format::ColorChoice::Auto)
+    }
+
+    /// Never use colors, ignoring the environment
+    #[cfg(feature = "color")]
+    pub fn without_color(self) -> Self {
+        self.with_color_choice(format::ColorChoice::Never)
+    }
+
+    /** Use this color choice, ignoring the environment
+
+    `Auto` only uses colors when stdout is a terminal, like [`with_color`](#method.with_color).
+    `Always` uses them even when stdout is piped to a file.
+    */
+    #[cfg(feature = "color")]
+    pub fn with_color_choice(mut self, choice: format::ColorChoice) -> Self {
+        self.use_color.replace(choice);
         self
     }
 
@@ -127,6 +556,14 @@ impl PrettyBuilder {
         self
     }
 
+    /// Use a `strftime`-style time format, see [`StrftimeTime`](../format/struct.StrftimeTime.html)
+    pub fn with_time_format(
+        self,
+        format: impl Into<String>,
+    ) -> Result<Self, format::TimeFormatError> {
+        format::StrftimeTime::new(format).map(|time| self.with_time(time))
+    }
+
     pub fn without_time(mut self) -> Self {
         self.time.take();
         self
@@ -142,6 +579,28 @@ impl PrettyBuilder {
         self
     }
 
+    /// Write the `file:line` of the log site after the target (and the module path)
+    pub fn with_location(mut self) -> Self {
+        self.location = true;
+        self
+    }
+
+    pub fn without_location(mut self) -> Self {
+        self.location = false;
+        self
+    }
+
+    /// Write the module path of the log site after the target
+    pub fn with_module_path(mut self) -> Self {
+        self.module_path = true;
+        self
+    }
+
+    pub fn without_module_path(mut self) -> Self {
+        self.module_path = false;
+        self
+    }
+
     pub fn with_level(mut self) -> Self {
         self.level = true;
         self
@@ -152,10 +611,61 @@ impl PrettyBuilder {
         self
     }
 
+    pub fn with_level_labels(mut self, labels: LevelLabels) -> Self {
+        self.level_labels = labels;
+        self
+    }
+
+    pub fn with_short_levels(self) -> Self {
+        self.with_level_labels(LevelLabels::short())
+    }
+
+    /// Escape control characters (other than newlines and tabs) in the message, e.g. `\x1b`
+    ///
+    /// This keeps untrusted input from moving the cursor or changing colors
+    pub fn with_control_char_escaping(mut self) -> Self {
+        self.escape_control = true;
+        self
+    }
+
+    pub fn without_control_char_escaping(mut self) -> Self {
+        self.escape_control = false;
+        self
+    }
+
+    /// Indent every line of a multi-line message (e.g. from `{:#?}`) to where the message starts
+    pub fn with_continuation_indent(mut self) -> Self {
+        self.continuation_indent = true;
+        self
+    }
+
+    pub fn without_continuation_indent(mut self) -> Self {
+        self.continuation_indent = false;
+        self
+    }
+
+    /// Start every record with this, e.g. `[worker-3]`, before any other field
+    ///
+    /// It's written in the target's color
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix.replace(prefix.into());
+        self
+    }
+
+    pub fn without_prefix(mut self) -> Self {
+        self.prefix.take();
+        self
+    }
+
+    /// Put the message on its own line, after this marker
+    ///
+    /// The other lines of a multi-line message start with the marker too, unless
+    /// `with_continuation_indent` lines them up with the first one instead.
+    /// `None` uses [`default_continuation`](../fn.default_continuation.html)
     pub fn with_continuation<'a>(mut self, cont: impl Into<Option<&'a str>>) -> Self {
         self.continuation.replace(
             cont.into()
-                .unwrap_or_else(|| crate::DEFAULT_CONTINUATION)
+                .unwrap_or_else(|| crate::default_continuation())
                 .to_string(),
         );
         self
@@ -166,22 +676,1621 @@ impl PrettyBuilder {
         self
     }
 
+    /// Use this separator between the fields, instead of a single space
+    ///
+    /// The target keeps its brackets, e.g. with `" | "`: `INFO  | [target] | message`
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Wrap lines at this many columns, `0` turns wrapping off
+    ///
+    /// Wrapped lines start with the continuation marker (or
+    /// [`default_continuation`](../fn.default_continuation.html) without one). This takes the
+    /// place of `with_continuation_indent`. Wide characters are measured best-effort.
+    pub fn with_wrap(mut self, width: usize) -> Self {
+        self.wrap = width;
+        self
+    }
+
+    /// Wrap lines at the width of the terminal, if stdout is one
+    #[cfg(feature = "terminal_size")]
+    pub fn with_wrap_auto(self) -> Self {
+        let width = terminal_size::terminal_size().map_or(0, |(w, _)| w.0 as usize);
+        self.with_wrap(width)
+    }
+
+    /// Write the time since the previous record after the timestamp, e.g. `+0.013s`
+    pub fn with_delta(mut self) -> Self {
+        self.delta = true;
+        self
+    }
+
+    pub fn without_delta(mut self) -> Self {
+        self.delta = false;
+        self
+    }
+
+    /// Write an increasing sequence number after the timestamp, e.g. `#00042`
+    ///
+    /// The counter is shared by every Pretty logger in the process, so gaps mean records were
+    /// lost (or filtered out by a wrapping logger)
+    pub fn with_sequence(mut self) -> Self {
+        self.sequence.get_or_insert(DEFAULT_SEQUENCE_WIDTH);
+        self
+    }
+
+    /// Zero-pad the sequence number to this many digits, instead of **5**
+    pub fn with_sequence_width(mut self, width: usize) -> Self {
+        self.sequence.replace(width);
+        self
+    }
+
+    pub fn without_sequence(mut self) -> Self {
+        self.sequence.take();
+        self
+    }
+
+    /// Write the name of the thread logging the record, after the sequence number
+    ///
+    /// Threads without a name get their id instead, e.g. `ThreadId(2)`
+    pub fn with_thread_name(mut self) -> Self {
+        self.thread_name = true;
+        self
+    }
+
+    pub fn without_thread_name(mut self) -> Self {
+        self.thread_name = false;
+        self
+    }
+
+    /// Write the id of the thread logging the record, e.g. `ThreadId(2)`, after its name
+    pub fn with_thread_id(mut self) -> Self {
+        self.thread_id = true;
+        self
+    }
+
+    pub fn without_thread_id(mut self) -> Self {
+        self.thread_id = false;
+        self
+    }
+
+    /// Leave out the target when it's the same as the previous line's, within **1** second
+    ///
+    /// Blanks are written in its place so the columns stay aligned
+    pub fn with_target_grouping(mut self) -> Self {
+        self.target_grouping.get_or_insert(DEFAULT_GROUPING_WINDOW);
+        self
+    }
+
+    /// Leave out the target when it's the same as the previous line's, within `window`
+    ///
+    /// The window restarts with every line, so a steady burst stays grouped
+    pub fn with_target_grouping_window(mut self, window: std::time::Duration) -> Self {
+        self.target_grouping.replace(window);
+        self
+    }
+
+    pub fn without_target_grouping(mut self) -> Self {
+        self.target_grouping.take();
+        self
+    }
+
+    /** Use the time carried by a record's `timestamp` key-value, instead of now
+
+    This is for replaying records logged earlier, or elsewhere. The value is the number of
+    seconds since the UNIX epoch, as an integer or a float, e.g.
+    `log::info!(timestamp = 1717245296.5; "replayed")`. Records without it use the current time.
+    The time formatter has to support this, see `FormatTime::format_time_at`.
+    */
+    #[cfg(feature = "kv")]
+    pub fn with_record_time(self) -> Self {
+        self.with_record_time_key("timestamp")
+    }
+
+    /// Use the time carried by a record's key-value with this key, instead of now
+    #[cfg(feature = "kv")]
+    pub fn with_record_time_key(mut self, key: &str) -> Self {
+        self.record_time_key.replace(key.to_string());
+        self
+    }
+
+    /// Always use the current time (the default)
+    #[cfg(feature = "kv")]
+    pub fn without_record_time(mut self) -> Self {
+        self.record_time_key.take();
+        self
+    }
+
+    /** Write the record's key-values after the message, e.g. `hello user=bob`
+
+    Values that are empty, or have spaces, `=` or `"` in them, are quoted like a Rust string,
+    e.g. `name="Bob Smith"`. When a key appears more than once, only its first value is written,
+    so a record's own key-values win over the context's.
+    */
+    #[cfg(feature = "kv")]
+    pub fn with_kv(mut self) -> Self {
+        self.key_values = true;
+        self
+    }
+
+    /// Leave out the record's key-values (the default)
+    #[cfg(feature = "kv")]
+    pub fn without_kv(mut self) -> Self {
+        self.key_values = false;
+        self
+    }
+
+    /// End lines with `\r\n`, for Windows tools expecting it
+    ///
+    /// This includes the continuation's new line and the newlines in the message
+    pub fn with_crlf(mut self) -> Self {
+        self.crlf = true;
+        self
+    }
+
+    /// End lines with `\n` (the default)
+    pub fn without_crlf(mut self) -> Self {
+        self.crlf = false;
+        self
+    }
+
+    /// Start each line with a symbol for the level, in the level's color, e.g. `‼` for `Error`
+    ///
+    /// This comes before everything else, see `LevelLabels::gutter` for the symbols
+    pub fn with_gutter(mut self) -> Self {
+        self.gutter.get_or_insert_with(LevelLabels::gutter);
+        self
+    }
+
+    /// Start each line with these symbols for the level
+    pub fn with_gutter_symbols(mut self, symbols: LevelLabels) -> Self {
+        self.gutter.replace(symbols);
+        self
+    }
+
+    pub fn without_gutter(mut self) -> Self {
+        self.gutter.take();
+        self
+    }
+
+    /// Write the message first and the other fields after it, like an access log
+    ///
+    /// The continuation isn't written, as there's nothing after the fields to separate
+    pub fn with_message_first(mut self) -> Self {
+        self.message_first = true;
+        self
+    }
+
+    /// Write the message last (the default)
+    pub fn without_message_first(mut self) -> Self {
+        self.message_first = false;
+        self
+    }
+
+    /** Write exactly these fields, in this order
+
+    This replaces the fields turned on or off with the other methods (e.g. `with_level`,
+    `with_message_first`), fields that aren't listed are left out. A `Timestamp` still needs a
+    time formatter and a `Continuation` still needs a continuation string, they're left out
+    otherwise. e.g. for a timestamp first, like syslog:
+    ```rust
+    # use arbitrary_logger::{format::{DateStyle, Utc}, logger::{Field, Pretty}};
+    let pretty = Pretty::builder()
+        .with_time(Utc::new(DateStyle::Calendar))
+        .with_field_order(&[Field::Timestamp, Field::Level, Field::Target, Field::Message])
+        .build();
+    ```
+    */
+    pub fn with_field_order(mut self, order: &[Field]) -> Self {
+        self.field_order.replace(order.to_vec());
+        self
+    }
+
+    /// Write the fields turned on with the other methods, in the default order
+    pub fn without_field_order(mut self) -> Self {
+        self.field_order.take();
+        self
+    }
+
+    /// Surround the target with these, e.g. `("<", ">")` for `<target>`
+    pub fn with_target_brackets(mut self, open: &str, close: &str) -> Self {
+        self.target_brackets = (open.to_string(), close.to_string());
+        self
+    }
+
+    /// Fit the target to `width` columns, so the messages line up
+    ///
+    /// Shorter targets are padded after the closing bracket, longer ones are cut short with `…`
+    pub fn with_target_width(mut self, width: usize) -> Self {
+        self.target_width = width;
+        self
+    }
+
+    /// Write the target as it is (the default)
+    pub fn without_target_width(mut self) -> Self {
+        self.target_width = 0;
+        self
+    }
+
+    /// Cut targets longer than `len` columns, keeping their end, e.g. `…::io::driver`
+    ///
+    /// The end is usually the most specific part of a target. This happens before
+    /// `with_target_width` fits the target
+    pub fn with_max_target_len(mut self, len: usize) -> Self {
+        self.max_target_len = len;
+        self
+    }
+
+    /// Write the whole target (the default)
+    pub fn without_max_target_len(mut self) -> Self {
+        self.max_target_len = 0;
+        self
+    }
+
+    /// Write the target without brackets
+    pub fn without_target_brackets(self) -> Self {
+        self.with_target_brackets("", "")
+    }
+
     pub fn uptime(self) -> Self {
         self.with_time(Uptime::default())
     }
 
+    pub fn uptime_seconds(self) -> Self {
+        self.with_time(Uptime::seconds())
+    }
+
+    pub fn uptime_millis(self) -> Self {
+        self.with_time(Uptime::millis())
+    }
+
+    pub fn uptime_micros(self) -> Self {
+        self.with_time(Uptime::micros())
+    }
+
     pub fn unix_timestamp(self, style: impl Into<Option<TimestampStyle>>) -> Self {
         self.with_time(Timestamp::new(style.into().unwrap_or_default()))
     }
 
+    pub fn utc(self, date: impl Into<Option<DateStyle>>) -> Self {
+        self.with_time(Utc::new(date.into().unwrap_or_default()))
+    }
+
+    pub fn rfc3339(self, style: impl Into<Option<TimestampStyle>>) -> Self {
+        self.with_time(format::Rfc3339::new(style.into().unwrap_or_default()))
+    }
+
+    /// Use the local time, see [`LocalTime`](../format/struct.LocalTime.html)
+    #[cfg(feature = "time")]
+    pub fn local_time(self, style: impl Into<Option<TimestampStyle>>) -> Self {
+        self.with_time(format::LocalTime::new(style.into().unwrap_or_default()))
+    }
+
+    /// Show everything: the level, the target, the log site, the thread and the uptime
+    pub fn verbose(self) -> Self {
+        self.with_level()
+            .with_target()
+            .with_location()
+            .with_thread_name()
+            .uptime()
+    }
+
+    /// Show just the message
+    pub fn minimal(self) -> Self {
+        let builder = self
+            .without_level()
+            .without_target()
+            .without_time()
+            .without_delta()
+            .without_sequence()
+            .without_continuation()
+            .without_location()
+            .without_module_path()
+            .without_thread_name()
+            .without_thread_id()
+            .without_gutter()
+            .without_prefix();
+        #[cfg(feature = "kv")]
+        let builder = builder.without_kv();
+        builder
+    }
+
+    /// Build the logger, checking for settings that contradict each other
+    ///
+    /// `build` accepts these, and one of the settings is ignored
+    pub fn try_build(self) -> Result<Pretty, BuildError> {
+        if self.wrap > 0 {
+            let cont = self
+                .continuation
+                .as_deref()
+                .unwrap_or_else(|| crate::default_continuation());
+            let needed = format::str_width(cont) + format::str_width(&self.separator) + 1;
+            if self.wrap < needed {
+                return Err(BuildError::WrapTooNarrow {
+                    width: self.wrap,
+                    needed,
+                });
+            }
+            if self.continuation_indent {
+                return Err(BuildError::WrapWithIndent);
+            }
+        }
+        if self.message_first && self.continuation.is_some() && self.field_order.is_none() {
+            return Err(BuildError::ContinuationWithMessageFirst);
+        }
+        if self.target_grouping.is_some() && !self.layout().contains(&Field::Target) {
+            return Err(BuildError::GroupingWithoutTarget);
+        }
+        #[cfg(feature = "color")]
+        if self.level_colored_continuation && self.continuation.is_none() && self.wrap == 0 {
+            return Err(BuildError::ColoredWithoutContinuation);
+        }
+        Ok(self.build())
+    }
+
     pub fn build(self) -> Pretty {
+        let layout = self.layout();
+        let delta = layout.contains(&Field::Delta);
+
         Pretty {
+            prefix: self.prefix,
             continuation: self.continuation,
-            level: self.level,
-            target: self.target,
+            separator: self.separator,
+            target_brackets: self.target_brackets,
+            target_width: self.target_width,
+            max_target_len: self.max_target_len,
+            level_labels: self.level_labels,
+            gutter: self.gutter.unwrap_or_else(LevelLabels::gutter),
+            escape_control: self.escape_control,
+            continuation_indent: self.continuation_indent,
+            level_colored_continuation: self.level_colored_continuation,
+            level_colored_message: self.level_colored_message,
+            crlf: self.crlf,
+            wrap: self.wrap,
+            target_grouping: self.target_grouping,
+            last_target: Default::default(),
+            sequence_width: self.sequence.unwrap_or(DEFAULT_SEQUENCE_WIDTH),
+            layout,
             time: self.time,
-            use_color: self.use_color,
+            #[cfg(feature = "kv")]
+            record_time_key: self.record_time_key,
+            #[cfg(feature = "kv")]
+            key_values: self.key_values,
+            delta: if delta { Some(Delta::new()) } else { None },
+            #[cfg(feature = "color")]
+            color_choice: color_choice(self.use_color),
             record_colors: self.record_colors,
         }
     }
+
+    fn layout(&self) -> Vec<Field> {
+        if let Some(order) = &self.field_order {
+            return order
+                .iter()
+                .copied()
+                .filter(|field| match field {
+                    Field::Timestamp => self.time.is_some(),
+                    Field::Continuation => self.continuation.is_some(),
+                    _ => true,
+                })
+                .collect();
+        }
+
+        let mut layout = vec![];
+        if self.gutter.is_some() {
+            layout.push(Field::Gutter);
+        }
+        if self.message_first {
+            layout.push(Field::Message);
+        }
+        for (field, enabled) in [
+            (Field::Level, self.level),
+            (Field::Target, self.target),
+            (Field::ModulePath, self.module_path),
+            (Field::Location, self.location),
+            (Field::Timestamp, self.time.is_some()),
+            (Field::Delta, self.delta),
+            (Field::Sequence, self.sequence.is_some()),
+            (Field::ThreadName, self.thread_name),
+            (Field::ThreadId, self.thread_id),
+            (
+                Field::Continuation,
+                self.continuation.is_some() && !self.message_first,
+            ),
+        ] {
+            if enabled {
+                layout.push(field);
+            }
+        }
+        if !self.message_first {
+            layout.push(Field::Message);
+        }
+        layout
+    }
+}
+
+/// Contradicting settings, found by [`PrettyBuilder::try_build`](./struct.PrettyBuilder.html#method.try_build)
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// The wrap width can't fit a continuation, a separator and a character
+    WrapTooNarrow { width: usize, needed: usize },
+    /// Wrapped lines start with the continuation, so the indent would be ignored
+    WrapWithIndent,
+    /// Nothing comes after the fields, so the continuation would be ignored
+    ContinuationWithMessageFirst,
+    /// The target isn't written, so target grouping would be ignored
+    GroupingWithoutTarget,
+    /// There's no continuation to color, neither a configured one nor one for wrapped lines
+    #[cfg(feature = "color")]
+    ColoredWithoutContinuation,
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrapTooNarrow { width, needed } => write!(
+                f,
+                "a wrap width of {} is too narrow, at least {} is needed",
+                width, needed
+            ),
+            Self::WrapWithIndent => f.write_str("wrapping can't be used with continuation indent"),
+            Self::ContinuationWithMessageFirst => {
+                f.write_str("a continuation can't be used with the message first")
+            }
+            Self::GroupingWithoutTarget => {
+                f.write_str("target grouping can't be used without the target")
+            }
+            #[cfg(feature = "color")]
+            Self::ColoredWithoutContinuation => {
+                f.write_str("a level colored continuation needs a continuation")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(pretty: &Pretty, record: &log::Record) -> String {
+        #[cfg(feature = "color")]
+        let mut buffer = Buffer::no_color();
+        #[cfg(not(feature = "color"))]
+        let mut buffer = Buffer::new();
+
+        pretty.render(record, &mut buffer).unwrap();
+        String::from_utf8(buffer.as_slice().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn default_layout() {
+        let pretty = Pretty::default();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello world"))
+                .level(log::Level::Info)
+                .target("foo::bar")
+                .build(),
+        );
+        assert_eq!(out, "INFO  [foo::bar] hello world\n");
+    }
+
+    #[test]
+    fn location() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_location()
+            .build();
+        let record = |file, line| {
+            render(
+                &pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .module_path(Some("foo::bar"))
+                    .file(file)
+                    .line(line)
+                    .build(),
+            )
+        };
+        assert_eq!(
+            record(Some("src/bar.rs"), Some(42)),
+            "INFO  [foo] src/bar.rs:42 hello\n"
+        );
+        assert_eq!(
+            record(Some("src/bar.rs"), None),
+            "INFO  [foo] src/bar.rs hello\n"
+        );
+        assert_eq!(record(None, Some(42)), "INFO  [foo] ?:42 hello\n");
+        assert_eq!(record(None, None), "INFO  [foo] ? hello\n");
+
+        let pretty = Pretty::builder()
+            .with_target()
+            .with_module_path()
+            .with_location()
+            .build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello"))
+                .target("foo")
+                .module_path(Some("foo::bar"))
+                .file(Some("src/bar.rs"))
+                .line(Some(7))
+                .build(),
+        );
+        assert_eq!(out, "[foo] foo::bar src/bar.rs:7 hello\n");
+
+        let pretty = Pretty::builder().with_module_path().build();
+        let out = render(
+            &pretty,
+            &log::Record::builder().args(format_args!("hello")).build(),
+        );
+        assert_eq!(out, "? hello\n");
+    }
+
+    #[test]
+    fn thread() {
+        let record = |pretty: Pretty| {
+            move || {
+                render(
+                    &pretty,
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(log::Level::Info)
+                        .target("foo")
+                        .build(),
+                )
+            }
+        };
+
+        let builder = || Pretty::builder().with_level();
+        let out = std::thread::Builder::new()
+            .name("worker-1".into())
+            .spawn(record(builder().with_thread_name().build()))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(out, "INFO  worker-1 hello\n");
+
+        let out = std::thread::spawn(record(builder().with_thread_name().build()))
+            .join()
+            .unwrap();
+        assert!(out.starts_with("INFO  ThreadId("), "{}", out);
+        assert!(out.ends_with(") hello\n"), "{}", out);
+
+        let handle = std::thread::Builder::new()
+            .name("worker-2".into())
+            .spawn(record(
+                builder()
+                    .with_sequence()
+                    .with_thread_name()
+                    .with_thread_id()
+                    .build(),
+            ))
+            .unwrap();
+        let id = handle.thread().id();
+        let out = handle.join().unwrap();
+        let expected = format!(" worker-2 {:?} hello\n", id);
+        assert!(out.ends_with(&expected), "{}", out);
+    }
+
+    #[test]
+    fn field_order() {
+        let record = log::Record::builder()
+            .args(format_args!("hello"))
+            .level(log::Level::Info)
+            .target("foo")
+            .line(Some(7))
+            .build();
+        let time = |w: &mut dyn std::io::Write| write!(w, "12:34");
+
+        let default = Pretty::builder().with_level().with_target().with_time(time);
+        assert_eq!(
+            render(&default.build(), &record),
+            "INFO  [foo] 12:34 hello\n"
+        );
+
+        let syslog = Pretty::builder().with_time(time).with_field_order(&[
+            Field::Timestamp,
+            Field::Level,
+            Field::Target,
+            Field::Message,
+        ]);
+        assert_eq!(
+            render(&syslog.build(), &record),
+            "12:34 INFO  [foo] hello\n"
+        );
+
+        // unlisted fields are left out, and a timestamp needs a formatter
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_field_order(&[Field::Message, Field::Timestamp, Field::Location]);
+        assert_eq!(render(&pretty.build(), &record), "hello ?:7\n");
+
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_field_order(&[Field::Message])
+            .without_field_order();
+        assert_eq!(render(&pretty.build(), &record), "INFO  hello\n");
+
+        assert!(Pretty::builder()
+            .with_field_order(&[Field::Target, Field::Message])
+            .with_target_grouping()
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn target_width() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_target_width(8)
+            .build();
+        let record = |target: &str| {
+            render(
+                &pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target(target)
+                    .build(),
+            )
+        };
+
+        assert_eq!(record("foo"), "INFO  [foo]      hello\n");
+        assert_eq!(record("foo::bar"), "INFO  [foo::bar] hello\n");
+        assert_eq!(record("foo::bar::baz"), "INFO  [foo::ba…] hello\n");
+        // a wide character that doesn't fit is replaced with padding
+        assert_eq!(record("foo::b日本"), "INFO  [foo::b…]  hello\n");
+        assert_eq!(record("foo::日本"), "INFO  [foo::日…] hello\n");
+
+        let grouped = Pretty::builder()
+            .with_target()
+            .with_target_width(4)
+            .with_target_grouping()
+            .build();
+        let record = |target: &str| {
+            render(
+                &grouped,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .target(target)
+                    .build(),
+            )
+        };
+        assert_eq!(record("foo::bar"), "[foo…] hello\n");
+        assert_eq!(record("foo::bar"), "       hello\n");
+    }
+
+    #[test]
+    fn max_target_len() {
+        let record = |pretty: &Pretty, target: &str| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .target(target)
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder()
+            .with_target()
+            .with_max_target_len(13)
+            .build();
+        assert_eq!(record(&pretty, "tokio::net"), "[tokio::net] hello\n");
+        assert_eq!(record(&pretty, "tokio::io::rt"), "[tokio::io::rt] hello\n");
+        assert_eq!(
+            record(&pretty, "tokio::runtime::io::driver"),
+            "[…::io::driver] hello\n"
+        );
+        assert_eq!(
+            record(&pretty, "tokio::io::日本語"),
+            "[…::io::日本語] hello\n"
+        );
+        assert_eq!(
+            record(&pretty, "tokio::i::日本語"),
+            "[…o::i::日本語] hello\n"
+        );
+
+        let pretty = Pretty::builder()
+            .with_target()
+            .with_max_target_len(1)
+            .build();
+        assert_eq!(record(&pretty, "ab"), "[…] hello\n");
+
+        let pretty = Pretty::builder()
+            .with_target()
+            .with_max_target_len(6)
+            .with_target_width(8)
+            .build();
+        assert_eq!(record(&pretty, "foo::bar"), "[…::bar]   hello\n");
+    }
+
+    #[test]
+    fn multi_line_continuation() {
+        let out = |pretty: &Pretty, msg: &str| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_continuation("->")
+            .build();
+        assert_eq!(
+            out(&pretty, "first\nsecond"),
+            "INFO \n-> first\n-> second\n"
+        );
+        assert_eq!(
+            out(&pretty, "first\nsecond\n"),
+            "INFO \n-> first\n-> second\n"
+        );
+        assert_eq!(
+            out(&pretty, "first\r\nsecond"),
+            "INFO \n-> first\n-> second\n"
+        );
+        assert_eq!(out(&pretty, "a\n\nb"), "INFO \n-> a\n-> \n-> b\n");
+
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_continuation("->")
+            .with_crlf()
+            .build();
+        assert_eq!(
+            out(&pretty, "first\r\nsecond\n"),
+            "INFO \r\n-> first\r\n-> second\r\n"
+        );
+
+        // without a continuation, the lines are left alone
+        let pretty = Pretty::builder().with_level().build();
+        assert_eq!(out(&pretty, "first\nsecond"), "INFO  first\nsecond\n");
+    }
+
+    #[test]
+    fn prefix() {
+        let record = |pretty: &Pretty| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello\nworld"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let builder = || Pretty::builder().with_level().with_prefix("[worker-3]");
+        assert_eq!(
+            record(&builder().build()),
+            "[worker-3] INFO  hello\nworld\n"
+        );
+        assert_eq!(
+            record(&builder().with_continuation("->").build()),
+            "[worker-3] INFO \n-> hello\n-> world\n"
+        );
+        assert_eq!(
+            record(&builder().with_field_order(&[Field::Message]).build()),
+            "[worker-3] hello\nworld\n"
+        );
+        assert_eq!(
+            record(&builder().without_prefix().build()),
+            "INFO  hello\nworld\n"
+        );
+    }
+
+    #[test]
+    fn message_only() {
+        let pretty = Pretty::builder().without_time().build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello world"))
+                .level(log::Level::Info)
+                .target("foo::bar")
+                .build(),
+        );
+        assert_eq!(out, "hello world\n");
+
+        let pretty = Pretty::builder().with_target().build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello world"))
+                .level(log::Level::Info)
+                .target("foo::bar")
+                .build(),
+        );
+        assert_eq!(out, "[foo::bar] hello world\n");
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn record_time() {
+        let out = |pretty: &Pretty, kvs: &[(&str, log::kv::Value)]| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .key_values(&kvs)
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder()
+            .with_time(crate::format::Timestamp::new(TimestampStyle::Fractional(1)))
+            .with_record_time()
+            .with_kv()
+            .build();
+        assert_eq!(
+            out(&pretty, &[("timestamp", 1717245296.into())]),
+            "1717245296.0 hello timestamp=1717245296\n"
+        );
+        assert_eq!(
+            out(&pretty, &[("timestamp", 1717245296.5.into())]),
+            "1717245296.5 hello timestamp=1717245296.5\n"
+        );
+        assert!(!out(&pretty, &[("timestamp", "soon".into())]).starts_with("1717245296"));
+
+        let pretty = Pretty::builder()
+            .utc(DateStyle::Calendar)
+            .with_record_time_key("ts")
+            .with_kv()
+            .build();
+        assert_eq!(
+            out(&pretty, &[("ts", 0.into())]),
+            "1970-01-01T00:00:00Z hello ts=0\n"
+        );
+    }
+
+    #[test]
+    fn try_build() {
+        assert!(Pretty::builder().minimal().try_build().is_ok());
+        assert!(Pretty::builder().with_wrap(80).try_build().is_ok());
+
+        let err = |builder: PrettyBuilder| builder.try_build().err();
+        assert_eq!(
+            err(Pretty::builder().with_continuation("->").with_wrap(3)),
+            Some(BuildError::WrapTooNarrow {
+                width: 3,
+                needed: 4
+            })
+        );
+        assert_eq!(
+            err(Pretty::builder().with_wrap(80).with_continuation_indent()),
+            Some(BuildError::WrapWithIndent)
+        );
+        assert_eq!(
+            err(Pretty::builder()
+                .with_message_first()
+                .with_continuation(None)),
+            Some(BuildError::ContinuationWithMessageFirst)
+        );
+        assert_eq!(
+            err(Pretty::builder().with_target_grouping_window(Default::default())),
+            Some(BuildError::GroupingWithoutTarget)
+        );
+
+        #[cfg(feature = "color")]
+        {
+            let colored = || {
+                Pretty::builder()
+                    .without_continuation()
+                    .with_level_colored_continuation()
+            };
+            assert_eq!(err(colored()), Some(BuildError::ColoredWithoutContinuation));
+            assert!(colored().with_wrap(40).try_build().is_ok());
+        }
+    }
+
+    #[test]
+    fn crlf() {
+        let out = |pretty: &Pretty, msg: &str| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder().with_crlf().build();
+        assert_eq!(out(&pretty, "hello"), "hello\r\n");
+        assert_eq!(out(&pretty, "a\nb\r\nc"), "a\r\nb\r\nc\r\n");
+
+        let pretty = Pretty::builder()
+            .with_crlf()
+            .with_target()
+            .with_continuation("|")
+            .build();
+        assert_eq!(out(&pretty, "hello"), "[foo]\r\n| hello\r\n");
+
+        let pretty = Pretty::builder()
+            .with_crlf()
+            .with_continuation_indent()
+            .with_control_char_escaping()
+            .with_target()
+            .build();
+        assert_eq!(out(&pretty, "a\nb"), "[foo] a\r\n      b\r\n");
+
+        let pretty = Pretty::builder().with_crlf().with_wrap(4).build();
+        assert_eq!(
+            out(&pretty, "ab cd"),
+            format!("ab\r\n{} cd\r\n", crate::default_continuation())
+        );
+
+        let pretty = Pretty::builder().with_crlf().with_wrap(40).build();
+        assert_eq!(
+            out(&pretty, "a\nb"),
+            format!("a\r\n{} b\r\n", crate::default_continuation())
+        );
+
+        let pretty = Pretty::builder().with_crlf().without_crlf().build();
+        assert_eq!(out(&pretty, "a\nb"), "a\nb\n");
+    }
+
+    #[test]
+    fn gutter() {
+        let record = |level| {
+            log::Record::builder()
+                .args(format_args!("hello"))
+                .level(level)
+                .target("foo")
+                .build()
+        };
+
+        let pretty = Pretty::builder().with_gutter().with_level().build();
+        assert_eq!(
+            render(&pretty, &record(log::Level::Error)),
+            "‼ ERROR hello\n"
+        );
+        assert_eq!(
+            render(&pretty, &record(log::Level::Info)),
+            "▶ INFO  hello\n"
+        );
+
+        let pretty = Pretty::builder()
+            .with_gutter_symbols(LevelLabels {
+                warn: "!!",
+                ..LevelLabels::short()
+            })
+            .with_message_first()
+            .with_target()
+            .build();
+        assert_eq!(
+            render(&pretty, &record(log::Level::Warn)),
+            "!! hello [foo]\n"
+        );
+        assert_eq!(
+            render(&pretty, &record(log::Level::Debug)),
+            "D  hello [foo]\n"
+        );
+
+        let pretty = Pretty::builder().with_gutter().without_gutter().build();
+        assert_eq!(render(&pretty, &record(log::Level::Info)), "hello\n");
+    }
+
+    #[test]
+    fn message_first() {
+        let record = log::Record::builder()
+            .args(format_args!("GET /index.html 200"))
+            .level(log::Level::Info)
+            .target("http")
+            .build();
+
+        let pretty = Pretty::builder()
+            .with_message_first()
+            .with_level()
+            .with_target()
+            .with_continuation("|")
+            .build();
+        assert_eq!(
+            render(&pretty, &record),
+            "GET /index.html 200 INFO  [http]\n"
+        );
+
+        let pretty = Pretty::builder().with_message_first().build();
+        assert_eq!(render(&pretty, &record), "GET /index.html 200\n");
+
+        let pretty = Pretty::builder()
+            .with_message_first()
+            .with_target()
+            .with_wrap(14)
+            .with_continuation("|")
+            .build();
+        assert_eq!(
+            render(&pretty, &record),
+            "GET\n| /index.html\n| 200 [http]\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn log_style() {
+        use termcolor::ColorChoice;
+        for (explicit, style, expected) in &[
+            (None, None, ColorChoice::Auto),
+            (None, Some("never"), ColorChoice::Never),
+            (None, Some("Always"), ColorChoice::Always),
+            (None, Some("auto"), ColorChoice::Auto),
+            (None, Some("bogus"), ColorChoice::Auto),
+            (Some(ColorChoice::Auto), Some("never"), ColorChoice::Auto),
+            (
+                Some(ColorChoice::Always),
+                Some("never"),
+                ColorChoice::Always,
+            ),
+            (Some(ColorChoice::Never), Some("always"), ColorChoice::Never),
+        ] {
+            let env = ColorEnv {
+                log_style: style.map(String::from),
+                is_terminal: true,
+                ..ColorEnv::default()
+            };
+            assert_eq!(
+                resolve_color_choice(*explicit, &env),
+                *expected,
+                "{:?} {:?}",
+                explicit,
+                style
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn no_color() {
+        use termcolor::ColorChoice;
+        for (explicit, style, expected) in &[
+            (None, None, ColorChoice::Never),
+            (Some(ColorChoice::Auto), None, ColorChoice::Auto),
+            (Some(ColorChoice::Always), None, ColorChoice::Always),
+            (Some(ColorChoice::Never), None, ColorChoice::Never),
+            (None, Some("always"), ColorChoice::Always),
+            (None, Some("auto"), ColorChoice::Never),
+        ] {
+            let env = ColorEnv {
+                log_style: style.map(String::from),
+                no_color: true,
+                clicolor_force: true,
+                is_terminal: true,
+            };
+            assert_eq!(
+                resolve_color_choice(*explicit, &env),
+                *expected,
+                "{:?} {:?}",
+                explicit,
+                style
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn terminal_and_clicolor_force() {
+        use termcolor::ColorChoice;
+        for (explicit, clicolor_force, is_terminal, expected) in &[
+            (None, false, false, ColorChoice::Never),
+            (None, false, true, ColorChoice::Auto),
+            (None, true, false, ColorChoice::Always),
+            (None, true, true, ColorChoice::Always),
+            (Some(ColorChoice::Auto), false, false, ColorChoice::Never),
+            (Some(ColorChoice::Auto), true, false, ColorChoice::Never),
+            (Some(ColorChoice::Auto), false, true, ColorChoice::Auto),
+            (Some(ColorChoice::Always), false, false, ColorChoice::Always),
+            (Some(ColorChoice::Never), true, true, ColorChoice::Never),
+        ] {
+            let env = ColorEnv {
+                clicolor_force: *clicolor_force,
+                is_terminal: *is_terminal,
+                ..ColorEnv::default()
+            };
+            assert_eq!(
+                resolve_color_choice(*explicit, &env),
+                *expected,
+                "{:?} {:?} {:?}",
+                explicit,
+                clicolor_force,
+                is_terminal
+            );
+        }
+    }
+
+    // the only test touching these variables, so it can't race with the others
+    #[test]
+    #[cfg(feature = "color")]
+    fn color_choice_from_env() {
+        use termcolor::ColorChoice;
+        std::env::remove_var(LOG_STYLE_KEY);
+
+        std::env::set_var(CLICOLOR_FORCE_KEY, "1");
+        let forced = color_choice(None);
+        std::env::set_var(NO_COLOR_KEY, "1");
+        let no_color = color_choice(None);
+        let explicit = color_choice(Some(ColorChoice::Always));
+        std::env::set_var(NO_COLOR_KEY, "");
+        let empty_no_color = color_choice(None);
+        let disabled = color_choice(Some(ColorChoice::Never));
+        std::env::remove_var(NO_COLOR_KEY);
+        std::env::remove_var(CLICOLOR_FORCE_KEY);
+
+        assert_eq!(forced, ColorChoice::Always);
+        assert_eq!(no_color, ColorChoice::Never);
+        assert_eq!(explicit, ColorChoice::Always);
+        assert_eq!(empty_no_color, ColorChoice::Always);
+        assert_eq!(disabled, ColorChoice::Never);
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn level_colored_continuation() {
+        let ansi = |pretty: &Pretty| {
+            let mut buffer = Buffer::ansi();
+            pretty
+                .render(
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(log::Level::Warn)
+                        .build(),
+                    &mut buffer,
+                )
+                .unwrap();
+            String::from_utf8(buffer.into_inner()).unwrap()
+        };
+
+        let builder = || Pretty::builder().with_level().with_continuation("->");
+        assert!(ansi(&builder().build()).contains("\x1b[38;5;237m->"));
+        assert!(ansi(&builder().with_level_colored_continuation().build()).contains("\x1b[33m->"));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn level_colored_message() {
+        let ansi = |pretty: &Pretty, level| {
+            let mut buffer = Buffer::ansi();
+            pretty
+                .render(
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(level)
+                        .build(),
+                    &mut buffer,
+                )
+                .unwrap();
+            String::from_utf8(buffer.into_inner()).unwrap()
+        };
+
+        let pretty = Pretty::builder().build();
+        assert!(ansi(&pretty, log::Level::Error).contains("\x1b[38;5;231mhello"));
+        assert!(ansi(&pretty, log::Level::Info).contains("\x1b[38;5;231mhello"));
+
+        let pretty = Pretty::builder().with_level_colored_message().build();
+        assert!(ansi(&pretty, log::Level::Error).contains("\x1b[31mhello"));
+        assert!(ansi(&pretty, log::Level::Info).contains("\x1b[32mhello"));
+
+        let pretty = Pretty::builder()
+            .with_level_colored_message()
+            .with_wrap(80)
+            .build();
+        assert!(ansi(&pretty, log::Level::Error).contains("\x1b[31mhello"));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn styled_fields() {
+        use crate::format::{Color, Style};
+
+        let mut colors = RecordColorConfig::default();
+        colors.level.warn = Style::new(Color::Yellow).with_bold();
+        colors.continuation = colors.continuation.with_dimmed();
+        let pretty = |builder: PrettyBuilder| {
+            let pretty = builder
+                .with_level()
+                .with_continuation("->")
+                .with_custom_colors(colors)
+                .build();
+            let mut buffer = Buffer::ansi();
+            pretty
+                .render(
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(log::Level::Warn)
+                        .build(),
+                    &mut buffer,
+                )
+                .unwrap();
+            String::from_utf8(buffer.into_inner()).unwrap()
+        };
+
+        let out = pretty(Pretty::builder());
+        assert!(out.contains("\x1b[1m\x1b[33mWARN"), "{:?}", out);
+        assert!(out.contains("\x1b[2m\x1b[38;5;237m->"), "{:?}", out);
+
+        let out = pretty(Pretty::builder().with_level_colored_continuation());
+        assert!(out.contains("\x1b[2m\x1b[33m->"), "{:?}", out);
+    }
+
+    #[test]
+    fn wrap() {
+        let record = |pretty: &Pretty, msg: &str| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder()
+            .with_target()
+            .with_continuation("->")
+            .with_wrap(12)
+            .build();
+        assert_eq!(
+            record(&pretty, "aaaa bbbb cccc dddddddddddd"),
+            "[foo]\n-> aaaa bbbb\n-> cccc\n-> ddddddddd\n-> ddd\n"
+        );
+        assert_eq!(record(&pretty, "a\nb"), "[foo]\n-> a\n-> b\n");
+        assert_eq!(record(&pretty, "a\n"), "[foo]\n-> a\n");
+        assert_eq!(record(&pretty, "a\r\nb"), "[foo]\n-> a\n-> b\n");
+        assert_eq!(
+            record(&pretty, "漢字漢字漢字"),
+            "[foo]\n-> 漢字漢字\n-> 漢字\n"
+        );
+
+        let pretty = Pretty::builder().with_target().with_wrap(12).build();
+        let out = record(&pretty, "one two three");
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "[foo] one");
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with(" two three"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn target_grouping() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_target_grouping_window(std::time::Duration::from_millis(200))
+            .build();
+        let record = |target: &str, msg: &str| {
+            render(
+                &pretty,
+                &log::Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(log::Level::Info)
+                    .target(target)
+                    .build(),
+            )
+        };
+
+        assert_eq!(record("foo", "a"), "INFO  [foo] a\n");
+        assert_eq!(record("foo", "b"), "INFO        b\n");
+        assert_eq!(record("bar", "c"), "INFO  [bar] c\n");
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(record("bar", "d"), "INFO  [bar] d\n");
+    }
+
+    #[test]
+    fn delta() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_time(|w: &mut dyn std::io::Write| write!(w, "42"))
+            .with_delta()
+            .build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello"))
+                .level(log::Level::Info)
+                .build(),
+        );
+        assert_eq!(out, "INFO  42 +0.000s hello\n");
+    }
+
+    #[test]
+    fn sequence() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_sequence_width(3)
+            .build();
+        let record = || {
+            render(
+                &pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .build(),
+            )
+        };
+
+        let seq = |line: String| -> u64 {
+            let (prefix, rest) = line.split_at("INFO  #".len());
+            assert_eq!(prefix, "INFO  #");
+            assert_eq!(&rest[3..], " hello\n");
+            rest[..3].parse().unwrap()
+        };
+
+        let first = seq(record());
+        let second = seq(record());
+        assert!(second > first, "{} {}", first, second);
+    }
+
+    #[test]
+    fn target_brackets() {
+        let record = |pretty: &Pretty| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let builder = || Pretty::builder().with_level().with_target();
+        assert_eq!(record(&builder().build()), "INFO  [foo] hello\n");
+        assert_eq!(
+            record(&builder().with_target_brackets("<", ">").build()),
+            "INFO  <foo> hello\n"
+        );
+        assert_eq!(
+            record(&builder().without_target_brackets().build()),
+            "INFO  foo hello\n"
+        );
+    }
+
+    #[test]
+    fn verbose_preset() {
+        let out = std::thread::Builder::new()
+            .name("worker-1".into())
+            .spawn(|| {
+                render(
+                    &Pretty::builder().verbose().build(),
+                    &log::Record::builder()
+                        .args(format_args!("hello"))
+                        .level(log::Level::Info)
+                        .target("foo")
+                        .file(Some("src/bar.rs"))
+                        .line(Some(42))
+                        .build(),
+                )
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(out.starts_with("INFO  [foo] src/bar.rs:42 0."), "{}", out);
+        assert!(out.ends_with("s worker-1 hello\n"), "{}", out);
+    }
+
+    #[test]
+    fn minimal_preset() {
+        #[cfg(feature = "kv")]
+        let kvs = [("user", "alice")];
+        let mut record = log::Record::builder();
+        record
+            .args(format_args!("hello"))
+            .level(log::Level::Info)
+            .target("foo");
+        #[cfg(feature = "kv")]
+        record.key_values(&kvs);
+
+        let pretty = Pretty::builder()
+            .verbose()
+            .with_sequence()
+            .with_delta()
+            .with_continuation("->")
+            .with_gutter()
+            .with_prefix("[worker-3]")
+            .with_module_path()
+            .with_thread_name()
+            .with_thread_id()
+            .minimal()
+            .build();
+        assert_eq!(render(&pretty, &record.build()), "hello\n");
+    }
+
+    #[test]
+    fn separator() {
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_time(|w: &mut dyn std::io::Write| write!(w, "42"))
+            .with_separator(" | ")
+            .build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello world"))
+                .level(log::Level::Warn)
+                .target("foo::bar")
+                .build(),
+        );
+        assert_eq!(out, "WARN  | [foo::bar] | 42 | hello world\n");
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn key_values() {
+        let record = |pretty: &Pretty| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(log::Level::Info)
+                    .target("foo")
+                    .key_values(&[("trace_id", "42"), ("span_id", "a1")])
+                    .build(),
+            )
+        };
+        assert_eq!(record(&Pretty::default()), "INFO  [foo] hello\n");
+        assert_eq!(
+            record(
+                &Pretty::builder()
+                    .with_level()
+                    .with_target()
+                    .with_kv()
+                    .build()
+            ),
+            "INFO  [foo] hello trace_id=42 span_id=a1\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn key_values_quoting() {
+        let out = |pretty: &Pretty, kvs: &[(&str, &str)]| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .key_values(&kvs)
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder().with_kv().build();
+        assert_eq!(
+            out(
+                &pretty,
+                &[
+                    ("user", "bob"),
+                    ("name", "Bob Smith"),
+                    ("query", "a=b"),
+                    ("said", "\"hi\""),
+                    ("empty", ""),
+                ]
+            ),
+            "hello user=bob name=\"Bob Smith\" query=\"a=b\" said=\"\\\"hi\\\"\" empty=\"\"\n"
+        );
+        assert_eq!(
+            out(&pretty, &[("user", "bob"), ("id", "1"), ("user", "alice")]),
+            "hello user=bob id=1\n"
+        );
+
+        let pretty = Pretty::builder().without_kv().build();
+        assert_eq!(out(&pretty, &[("user", "bob")]), "hello\n");
+    }
+
+    #[test]
+    fn control_char_escaping() {
+        let record = |pretty: &Pretty| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("a\x1b[31mb\rc\nd\te\x7f"))
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder().build();
+        assert_eq!(record(&pretty), "a\x1b[31mb\rc\nd\te\x7f\n");
+
+        let pretty = Pretty::builder().with_control_char_escaping().build();
+        assert_eq!(record(&pretty), "a\\x1b[31mb\\x0dc\nd\te\\x7f\n");
+
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("\u{9b}31m red\u{85}é"))
+                .build(),
+        );
+        assert_eq!(out, "\\u{9b}31m red\\u{85}é\n");
+    }
+
+    #[test]
+    fn continuation_indent() {
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        let record = |pretty: &Pretty| {
+            render(
+                pretty,
+                &log::Record::builder()
+                    .args(format_args!("{:#?}", Point { x: 1, y: 2 }))
+                    .level(log::Level::Debug)
+                    .target("foo")
+                    .build(),
+            )
+        };
+
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_continuation_indent()
+            .build();
+        assert_eq!(
+            record(&pretty),
+            "DEBUG [foo] Point {\n                x: 1,\n                y: 2,\n            }\n"
+        );
+
+        let pretty = Pretty::builder()
+            .with_level()
+            .with_target()
+            .with_continuation("->")
+            .with_continuation_indent()
+            .build();
+        assert_eq!(
+            record(&pretty),
+            "DEBUG [foo]\n-> Point {\n       x: 1,\n       y: 2,\n   }\n"
+        );
+    }
+
+    #[test]
+    fn level_padding() {
+        let record = |level| {
+            let pretty = Pretty::builder()
+                .with_level()
+                .with_level_labels(LevelLabels {
+                    warn: "WARNING",
+                    ..LevelLabels::default()
+                })
+                .build();
+            render(
+                &pretty,
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .level(level)
+                    .build(),
+            )
+        };
+        assert_eq!(record(log::Level::Info), "INFO    hello\n");
+        assert_eq!(record(log::Level::Warn), "WARNING hello\n");
+
+        let pretty = Pretty::builder().with_level().with_short_levels().build();
+        let out = render(
+            &pretty,
+            &log::Record::builder()
+                .args(format_args!("hello"))
+                .level(log::Level::Debug)
+                .build(),
+        );
+        assert_eq!(out, "D hello\n");
+    }
 }
@@ -0,0 +1,160 @@
+//! A target rewriting logger
+//!
+use crate::format::Print;
+
+use std::borrow::Cow;
+
+/** A logger that rewrites the target of each record
+
+Because a `log::Record` can't be changed, a new one is built with the rewritten target and the
+rest of the record (level, message, location and key-values) borrowed from the original. That is
+cheap, the cost is in the rewrite itself: return `Cow::Borrowed` for targets that don't change
+to avoid an allocation per record.
+
+This is both a [`Print`](../format/trait.Print.html), to rewrite targets just before formatting,
+and a `log::Log`, to rewrite them before filtering. For the latter, wrap a logger from
+[`build`](./fn.build.html) and install it with `log::set_boxed_logger`.
+
+# Usage
+```rust
+# use arbitrary_logger::logger::{self, Pretty, TargetRewrite};
+# use arbitrary_logger::filtered::Filtered;
+# use std::borrow::Cow;
+let logger = logger::build(Pretty::default(), "trace", Filtered::new(["svc_a::hyper=debug"]))?;
+let logger = TargetRewrite::new(logger, |target: &str| match target {
+    t if t.starts_with("hyper") => Cow::Owned(format!("svc_a::{}", t)),
+    t => Cow::Borrowed(t),
+});
+log::set_boxed_logger(Box::new(logger))?;
+log::set_max_level(log::LevelFilter::Trace);
+# Ok::<_, Box<dyn std::error::Error>>(())
+```
+*/
+pub struct TargetRewrite<L, F> {
+    inner: L,
+    rewrite: F,
+}
+
+impl<L, F> TargetRewrite<L, F>
+where
+    F: for<'a> Fn(&'a str) -> Cow<'a, str> + Send + Sync,
+{
+    /// Wrap a logger, rewriting targets with `rewrite`
+    pub fn new(inner: L, rewrite: F) -> Self {
+        Self { inner, rewrite }
+    }
+
+    fn with_record<T>(&self, record: &log::Record, f: impl FnOnce(&log::Record) -> T) -> T {
+        let target = (self.rewrite)(record.target());
+        let mut builder = log::Record::builder();
+        builder
+            .args(*record.args())
+            .level(record.level())
+            .target(&target)
+            .module_path(record.module_path())
+            .file(record.file())
+            .line(record.line());
+        #[cfg(feature = "kv")]
+        builder.key_values(record.key_values());
+        f(&builder.build())
+    }
+}
+
+impl<L, F> Print for TargetRewrite<L, F>
+where
+    L: Print,
+    F: for<'a> Fn(&'a str) -> Cow<'a, str> + Send + Sync,
+{
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        self.with_record(record, |record| self.inner.print(record))
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<L, F> log::Log for TargetRewrite<L, F>
+where
+    L: log::Log,
+    F: for<'a> Fn(&'a str) -> Cow<'a, str> + Send + Sync,
+{
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        let target = (self.rewrite)(metadata.target());
+        self.inner.enabled(
+            &log::Metadata::builder()
+                .level(metadata.level())
+                .target(&target)
+                .build(),
+        )
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.with_record(record, |record| self.inner.log(record))
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Capture;
+    use std::sync::{Arc, Mutex};
+
+    fn prefix(target: &str) -> Cow<'_, str> {
+        match target {
+            t if t.starts_with("hyper") => Cow::Owned(format!("svc_a::{}", t)),
+            t => Cow::Borrowed(t),
+        }
+    }
+
+    #[test]
+    fn before_filtering() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let logger = crate::logger::build(
+            Capture::with_target(&lines),
+            "trace",
+            crate::filtered::Filtered::new(["svc_a::hyper=debug"]),
+        )
+        .unwrap();
+        let logger = TargetRewrite::new(logger, prefix);
+
+        for (target, level) in &[
+            ("hyper::client", log::Level::Debug),
+            ("hyper::client", log::Level::Info),
+            ("tokio", log::Level::Debug),
+        ] {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .args(format_args!("{}", level))
+                    .level(*level)
+                    .target(target)
+                    .build(),
+            );
+        }
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["svc_a::hyper::client INFO", "tokio DEBUG"]
+        );
+    }
+
+    #[test]
+    fn print() {
+        let lines = Arc::<Mutex<Vec<String>>>::default();
+        let logger = TargetRewrite::new(Capture::with_target(&lines), prefix);
+        logger
+            .print(
+                &log::Record::builder()
+                    .args(format_args!("hello"))
+                    .target("hyper")
+                    .build(),
+            )
+            .unwrap();
+        assert_eq!(*lines.lock().unwrap(), vec!["svc_a::hyper hello"]);
+    }
+}
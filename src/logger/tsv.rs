@@ -0,0 +1,128 @@
+//! A tab-separated logger
+//!
+use crate::format::{FormatTime, Print, Render};
+use std::io::Write;
+
+/** A tab-separated logger, for `awk`, `cut` or `column -t`
+
+Each record is a single line of `level\ttarget\ttimestamp\tmessage`. The timestamp column is
+empty when no time is configured.
+
+Tabs, newlines, carriage returns and backslashes in the target and message are escaped as
+`\t`, `\n`, `\r` and `\\`
+*/
+#[derive(Default)]
+pub struct Tsv {
+    time: Option<Box<dyn FormatTime>>,
+}
+
+impl Tsv {
+    /// Create a tab-separated logger without a timestamp
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this time formatter for the timestamp column
+    pub fn with_time<F: FormatTime + 'static>(mut self, time: F) -> Self {
+        self.time.replace(Box::new(time));
+        self
+    }
+
+    fn render(&self, record: &log::Record, buffer: &mut Vec<u8>) -> std::io::Result<()> {
+        write!(buffer, "{}\t", record.level())?;
+        write!(Escape(buffer), "{}", record.target())?;
+        write!(buffer, "\t")?;
+        if let Some(time) = self.time.as_deref() {
+            time.format_time(buffer)?;
+        }
+        write!(buffer, "\t")?;
+        write!(Escape(buffer), "{}", record.args())?;
+        writeln!(buffer)
+    }
+}
+
+/// Renders the same line as it prints
+impl Render for Tsv {
+    fn render(&self, record: &log::Record, w: &mut dyn Write) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        Tsv::render(self, record, &mut buffer)?;
+        w.write_all(&buffer)
+    }
+}
+
+impl Print for Tsv {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        Tsv::render(self, record, &mut buffer)?;
+        std::io::stdout().lock().write_all(&buffer)
+    }
+}
+
+struct Escape<'a>(&'a mut Vec<u8>);
+
+impl Write for Escape<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for &b in buf {
+            match b {
+                b'\t' => self.0.extend_from_slice(b"\\t"),
+                b'\n' => self.0.extend_from_slice(b"\\n"),
+                b'\r' => self.0.extend_from_slice(b"\\r"),
+                b'\\' => self.0.extend_from_slice(b"\\\\"),
+                b => self.0.push(b),
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(tsv: &Tsv, record: &log::Record) -> String {
+        let mut buffer = vec![];
+        Tsv::render(tsv, record, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn columns() {
+        let tsv = Tsv::new().with_time(|w: &mut dyn std::io::Write| write!(w, "42"));
+        let out = render(
+            &tsv,
+            &log::Record::builder()
+                .args(format_args!("hello world"))
+                .level(log::Level::Info)
+                .target("foo::bar")
+                .build(),
+        );
+        assert_eq!(out, "INFO\tfoo::bar\t42\thello world\n");
+
+        let out = render(
+            &Tsv::new(),
+            &log::Record::builder()
+                .args(format_args!("hello"))
+                .level(log::Level::Warn)
+                .target("foo")
+                .build(),
+        );
+        assert_eq!(out, "WARN\tfoo\t\thello\n");
+    }
+
+    #[test]
+    fn escaping() {
+        let out = render(
+            &Tsv::new(),
+            &log::Record::builder()
+                .args(format_args!("a\tb\nc\r\nd\\e"))
+                .level(log::Level::Error)
+                .target("foo")
+                .build(),
+        );
+        assert_eq!(out, "ERROR\tfoo\t\ta\\tb\\nc\\r\\nd\\\\e\n");
+    }
+}
@@ -0,0 +1,186 @@
+//! A UDP logger
+//!
+use crate::format::{Print, Render};
+
+use std::hash::{Hash, Hasher};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The magic bytes starting a GELF chunk
+const GELF_MAGIC: [u8; 2] = [0x1e, 0x0f];
+
+/// The size of a GELF chunk header: magic, message id, sequence number and count
+const GELF_HEADER: usize = 12;
+
+/// GELF allows at most this many chunks per message
+const GELF_MAX_CHUNKS: usize = 128;
+
+/** A logger that sends each record as a UDP datagram
+
+Each record is rendered (without its trailing newline) and sent as a single datagram to the
+connected address. This is lossy: nothing is retried and nothing is reconnected. Send errors are
+returned from `print`, so they are handled by the logger's
+[`OnError`](../enum.OnError.html) policy.
+
+Records larger than a datagram can be split into
+[GELF chunks](https://go2docs.graylog.org/current/getting_in_log_data/gelf.html) with
+`with_gelf_chunking`. Otherwise sending them fails.
+
+# Usage
+```rust,no_run
+# use arbitrary_logger::logger::{Tsv, UdpSink};
+let logger = UdpSink::new(Tsv::new(), "127.0.0.1:12201").unwrap();
+arbitrary_logger::try_init(logger, log::LevelFilter::Trace);
+```
+*/
+pub struct UdpSink<R> {
+    render: R,
+    socket: UdpSocket,
+    chunk_size: Option<usize>,
+}
+
+impl<R: Render> UdpSink<R> {
+    /// Render records with `render`, sending them to `addr` from an ephemeral port
+    pub fn new(render: R, addr: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "no address to send to")
+        })?;
+        let local: SocketAddr = if addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0; 16], 0).into()
+        };
+
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(Self {
+            render,
+            socket,
+            chunk_size: None,
+        })
+    }
+
+    /// Split records larger than `max_size` bytes into GELF chunks of at most `max_size` bytes
+    ///
+    /// Graylog recommends `1420` for a WAN and `8192` for a LAN. At most 128 chunks are sent,
+    /// a record needing more is an error.
+    pub fn with_gelf_chunking(mut self, max_size: usize) -> Self {
+        self.chunk_size.replace(max_size.max(GELF_HEADER + 1));
+        self
+    }
+
+    fn send_chunked(&self, payload: &[u8], max_size: usize) -> std::io::Result<()> {
+        let chunks = payload.chunks(max_size - GELF_HEADER);
+        let count = chunks.len();
+        if count > GELF_MAX_CHUNKS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "record needs {} GELF chunks, at most 128 are allowed",
+                    count
+                ),
+            ));
+        }
+
+        let id = message_id();
+        let mut datagram = Vec::with_capacity(max_size);
+        for (seq, chunk) in chunks.enumerate() {
+            datagram.clear();
+            datagram.extend_from_slice(&GELF_MAGIC);
+            datagram.extend_from_slice(&id);
+            datagram.extend_from_slice(&[seq as u8, count as u8]);
+            datagram.extend_from_slice(chunk);
+            self.socket.send(&datagram)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Render> Print for UdpSink<R> {
+    fn print(&self, record: &log::Record) -> std::io::Result<()> {
+        let mut payload = vec![];
+        self.render.render(record, &mut payload)?;
+        if payload.last() == Some(&b'\n') {
+            payload.pop();
+        }
+
+        match self.chunk_size {
+            Some(max_size) if payload.len() > max_size => self.send_chunked(&payload, max_size),
+            _ => self.socket.send(&payload).map(drop),
+        }
+    }
+}
+
+// a GELF message id only has to be unique among the messages in flight
+fn message_id() -> [u8; 8] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    COUNTER.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+    std::process::id().hash(&mut hasher);
+    std::time::SystemTime::now().hash(&mut hasher);
+    hasher.finish().to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Message;
+
+    fn receiver() -> (UdpSocket, SocketAddr) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        (socket, addr)
+    }
+
+    fn recv(socket: &UdpSocket) -> Vec<u8> {
+        let mut buf = [0; 1024];
+        let n = socket.recv(&mut buf).unwrap();
+        buf[..n].to_vec()
+    }
+
+    fn print(logger: &impl Print, msg: &str) -> std::io::Result<()> {
+        logger.print(&log::Record::builder().args(format_args!("{}", msg)).build())
+    }
+
+    #[test]
+    fn datagram() {
+        let (socket, addr) = receiver();
+        let logger = UdpSink::new(Message, addr).unwrap();
+
+        print(&logger, "hello").unwrap();
+        print(&logger, "world").unwrap();
+        assert_eq!(recv(&socket), b"hello");
+        assert_eq!(recv(&socket), b"world");
+    }
+
+    #[test]
+    fn gelf_chunking() {
+        let (socket, addr) = receiver();
+        let logger = UdpSink::new(Message, addr)
+            .unwrap()
+            .with_gelf_chunking(GELF_HEADER + 4);
+
+        print(&logger, "small enough").unwrap();
+        assert_eq!(recv(&socket), b"small enough");
+
+        print(&logger, "abcdefghijklmnopqrst").unwrap();
+        let chunks = (0..5).map(|_| recv(&socket)).collect::<Vec<_>>();
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk[..2], GELF_MAGIC);
+            assert_eq!(chunk[2..10], chunks[0][2..10], "same message id");
+            assert_eq!(chunk[10..12], [i as u8, 5]);
+        }
+        let payload = chunks
+            .iter()
+            .flat_map(|c| &c[12..])
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(payload, b"abcdefghijklmnopqrst");
+
+        assert!(print(&logger, &"a".repeat(4 * 129)).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use signal_hook::consts::{SIGUSR1, SIGUSR2};
+
+/** Change the minimum level of the installed logger with signals
+
+This installs handlers for `SIGUSR1` and `SIGUSR2`, and spawns a thread waiting for them:
+- `SIGUSR1` makes the logger more verbose: `Off` → `Error` → `Warn` → `Info` → `Debug` → `Trace`
+- `SIGUSR2` makes it quieter, in the opposite order
+
+The level stops at either end rather than wrapping around. Each change is logged as a `Warn`
+record targeted at `arbitrary_logger` (unless the new level hides it). The level can't go past
+`log::STATIC_MAX_LEVEL`, see [`set_min_level`](./fn.set_min_level.html).
+
+This replaces the default action of those signals, which terminates the process.
+
+# Usage
+```rust,no_run
+arbitrary_logger::try_init(arbitrary_logger::logger::Pretty::default(), "info");
+arbitrary_logger::install_sigusr_handler()?;
+// then: kill -USR1 <pid>
+# Ok::<_, std::io::Error>(())
+```
+*/
+pub fn install_sigusr_handler() -> std::io::Result<()> {
+    let mut signals = signal_hook::iterator::Signals::new([SIGUSR1, SIGUSR2])?;
+    std::thread::Builder::new()
+        .name("arbitrary_logger".into())
+        .spawn(move || {
+            for signal in signals.forever() {
+                let current = match crate::min_level() {
+                    Some(current) => current,
+                    None => continue,
+                };
+                let next = next_level(signal, current, log::STATIC_MAX_LEVEL);
+                let _ = crate::set_min_level(next);
+                log::warn!(target: "arbitrary_logger", "minimum level is now {}", next);
+            }
+        })?;
+    Ok(())
+}
+
+// the cap is a parameter so the tests don't depend on the `log` features
+fn next_level(signal: i32, current: log::LevelFilter, max: log::LevelFilter) -> log::LevelFilter {
+    let next = match signal {
+        SIGUSR1 => more_verbose(current),
+        _ => quieter(current),
+    };
+    next.min(max)
+}
+
+fn more_verbose(level: log::LevelFilter) -> log::LevelFilter {
+    log::LevelFilter::iter()
+        .find(|&next| next > level)
+        .unwrap_or(log::LevelFilter::Trace)
+}
+
+fn quieter(level: log::LevelFilter) -> log::LevelFilter {
+    log::LevelFilter::iter()
+        .take_while(|&next| next < level)
+        .last()
+        .unwrap_or(log::LevelFilter::Off)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::LevelFilter::*;
+
+    #[test]
+    fn cycle_order() {
+        let up = [Off, Error, Warn, Info, Debug, Trace, Trace];
+        for pair in up.windows(2) {
+            assert_eq!(more_verbose(pair[0]), pair[1]);
+        }
+
+        let down = [Trace, Debug, Info, Warn, Error, Off, Off];
+        for pair in down.windows(2) {
+            assert_eq!(quieter(pair[0]), pair[1]);
+        }
+    }
+
+    #[test]
+    fn capped() {
+        assert_eq!(next_level(SIGUSR1, Warn, Info), Info);
+        assert_eq!(next_level(SIGUSR1, Info, Info), Info);
+        assert_eq!(next_level(SIGUSR2, Info, Info), Warn);
+        assert_eq!(next_level(SIGUSR1, Debug, Trace), Trace);
+    }
+}